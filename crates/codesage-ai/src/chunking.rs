@@ -0,0 +1,78 @@
+//! Token-aware splitting of large files into overlapping review windows
+
+use tiktoken_rs::cl100k_base_singleton;
+
+/// A contiguous slice of a file's lines, tagged with the 1-based line number
+/// it starts at so issues reported against it can be shifted back into
+/// absolute file coordinates.
+pub struct SourceWindow {
+    pub start_line: usize,
+    pub text: String,
+}
+
+/// Lines repeated at the start of the next window so issues spanning a chunk
+/// boundary aren't missed.
+const OVERLAP_LINES: usize = 10;
+
+/// Count tokens using the cl100k_base encoder. Anthropic doesn't publish an
+/// open tokenizer for Claude, so this is used as a close enough proxy for
+/// budgeting purposes.
+///
+/// `split_into_windows` calls this once per candidate line while growing
+/// each window, so the encoder is fetched via `cl100k_base_singleton()`
+/// rather than rebuilt from its ~100k-entry merge table on every call.
+pub fn count_tokens(text: &str) -> usize {
+    let bpe = cl100k_base_singleton();
+    let bpe = bpe.lock();
+    bpe.encode_with_special_tokens(text).len()
+}
+
+/// Split `source` into line-aligned windows that each fit within
+/// `budget_tokens`, overlapping by `OVERLAP_LINES` so a single review prompt
+/// never overflows the model's context window.
+pub fn split_into_windows(source: &str, budget_tokens: usize) -> Vec<SourceWindow> {
+    let lines: Vec<&str> = source.lines().collect();
+    if lines.is_empty() {
+        return vec![SourceWindow {
+            start_line: 1,
+            text: String::new(),
+        }];
+    }
+
+    let mut windows = Vec::new();
+    let mut start = 0usize;
+
+    while start < lines.len() {
+        let mut end = start;
+        let mut text = String::new();
+
+        while end < lines.len() {
+            let candidate = if text.is_empty() {
+                lines[end].to_string()
+            } else {
+                format!("{}\n{}", text, lines[end])
+            };
+
+            // Always take at least one line, even if it alone blows the budget.
+            if end > start && count_tokens(&candidate) > budget_tokens {
+                break;
+            }
+
+            text = candidate;
+            end += 1;
+        }
+
+        windows.push(SourceWindow {
+            start_line: start + 1,
+            text,
+        });
+
+        if end >= lines.len() {
+            break;
+        }
+
+        start = end.saturating_sub(OVERLAP_LINES).max(start + 1);
+    }
+
+    windows
+}
@@ -1,11 +1,36 @@
 //! AI client implementation
 
+use crate::chunking::{self, SourceWindow};
+use crate::provider::{self, CompletionOutcome, LlmProvider, Provider, ToolSpec};
+use crate::semantic_index::{RetrievedChunk, SemanticIndex};
 use async_trait::async_trait;
 use codesage_core::{
-    AIReviewer, AnalysisContext, CodeMetrics, CodeReviewResult, CodeSageError, Issue,
+    AIReviewer, AnalysisContext, CodeMetrics, CodeReviewResult, CodeSageError, Fix, Issue,
     IssueCategory, Location, Result, Severity,
 };
-use serde::{Deserialize, Serialize};
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Number of related chunks pulled from the semantic index into a review
+/// prompt's "Related code" section.
+const RELATED_CHUNKS_TOP_K: usize = 5;
+
+/// Number of chunk requests to have in flight at once when a file has to be
+/// split across multiple windows.
+const MAX_CONCURRENT_WINDOWS: usize = 4;
+
+/// Tokens reserved for the model's completion, subtracted from
+/// `context_window_tokens` when deciding whether a file needs chunking.
+const RESERVED_COMPLETION_TOKENS: usize = 4096;
+
+const REPORT_ISSUES_TOOL: &str = "report_issues";
+const REPORT_SUMMARY_TOOL: &str = "report_summary";
+
+/// Cap on how many issues `summarize_findings` lists in its prompt, so a
+/// large recursive review doesn't blow the context window on the summary
+/// pass itself.
+const MAX_SUMMARY_ISSUES: usize = 200;
 
 /// Configuration for AI client
 #[derive(Debug, Clone)]
@@ -14,6 +39,12 @@ pub struct AIConfig {
     pub model: String,
     pub api_base_url: String,
     pub timeout_seconds: u64,
+    /// Maximum tokens (prompt + completion) the configured model accepts.
+    /// Files whose prompt would exceed this are split into overlapping
+    /// windows by `chunking::split_into_windows`.
+    pub context_window_tokens: usize,
+    /// Which backend `provider::build_provider` should construct.
+    pub provider: Provider,
 }
 
 impl Default for AIConfig {
@@ -23,37 +54,66 @@ impl Default for AIConfig {
             model: "claude-3-5-sonnet-20241022".to_string(),
             api_base_url: "https://api.anthropic.com/v1".to_string(),
             timeout_seconds: 60,
+            context_window_tokens: 180_000,
+            provider: Provider::Anthropic,
         }
     }
 }
 
-/// AI client for code review
+/// AI client for code review. The wire format for whichever backend
+/// `config.provider` selects is handled entirely by `provider`; this struct
+/// only knows about prompts, chunking, and `Issue`s.
 pub struct AIClient {
     config: AIConfig,
-    client: reqwest::Client,
+    provider: Box<dyn LlmProvider>,
 }
 
-#[derive(Serialize)]
-struct ClaudeRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
+/// Shape of the `report_issues` tool input, mirroring `Issue` but addressing
+/// source lines directly instead of a full `Location`.
+#[derive(Deserialize)]
+struct ReportIssuesInput {
+    issues: Vec<ReportedIssue>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+/// Shape of the `report_summary` tool input.
+#[derive(Deserialize)]
+struct ReportSummaryInput {
+    summary: String,
 }
 
 #[derive(Deserialize)]
-struct ClaudeResponse {
-    content: Vec<ContentBlock>,
+struct ReportedIssue {
+    id: String,
+    severity: Severity,
+    category: IssueCategory,
+    start_line: usize,
+    end_line: usize,
+    message: String,
+    explanation: String,
+    fix_suggestion: Option<Fix>,
+    confidence: f32,
 }
 
-#[derive(Deserialize)]
-struct ContentBlock {
-    text: String,
+impl ReportedIssue {
+    fn into_issue(self, context: &AnalysisContext) -> Issue {
+        Issue {
+            id: self.id,
+            severity: self.severity,
+            category: self.category,
+            location: Location {
+                file_path: context.file_path.clone(),
+                start_line: self.start_line,
+                start_column: 1,
+                end_line: self.end_line,
+                end_column: 1,
+            },
+            message: self.message,
+            explanation: self.explanation,
+            fix_suggestion: self.fix_suggestion,
+            confidence: self.confidence,
+            related_locations: Vec::new(),
+        }
+    }
 }
 
 impl AIClient {
@@ -64,16 +124,25 @@ impl AIClient {
 
     /// Create a new AI client with custom configuration
     pub fn with_config(config: AIConfig) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
-            .build()
-            .expect("Failed to create HTTP client");
+        let provider = provider::build_provider(&config);
+        Self { config, provider }
+    }
 
-        Self { config, client }
+    /// Embed `text` using the configured provider.
+    pub async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.provider.embed(text).await
     }
 
-    /// Build the review prompt
+    /// Build the review prompt for the full file
     fn build_review_prompt(&self, context: &AnalysisContext) -> String {
+        self.build_review_prompt_for(context, &context.source_code)
+    }
+
+    /// Build the review prompt for `source`, which may be the whole file or
+    /// a single chunk produced by `chunking::split_into_windows`. Issues the
+    /// model reports carry line numbers relative to `source`; callers are
+    /// responsible for shifting them back to absolute file coordinates.
+    fn build_review_prompt_for(&self, context: &AnalysisContext, source: &str) -> String {
         let lang = format!("{:?}", context.language);
         let lang_lower = lang.to_lowercase();
         format!(
@@ -94,115 +163,292 @@ Please analyze for:
 4. Code quality and maintainability
 5. Best practices violations
 
-Provide specific, actionable feedback."#,
+Line numbers in the code above start at 1. Report every issue you find using
+the report_issues tool, with start_line/end_line relative to this snippet."#,
             context.file_path.display(),
-            context.source_code.lines().count(),
-            context.source_code
+            source.lines().count(),
+            source
         )
     }
 
-    /// Call Claude API (mock implementation for now)
-    async fn call_claude_api(&self, prompt: String) -> Result<String> {
-        // Check if API key is available
-        let api_key = self
-            .config
-            .api_key
-            .as_ref()
-            .ok_or_else(|| CodeSageError::AIError("ANTHROPIC_API_KEY not set".to_string()))?;
-
-        let request = ClaudeRequest {
-            model: self.config.model.clone(),
-            max_tokens: 4096,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
+    /// Review `context`, injecting the most relevant chunks from `index` as a
+    /// "Related code" section so the model can see callers and related
+    /// modules instead of just the isolated file.
+    pub async fn review_with_index(
+        &self,
+        context: &AnalysisContext,
+        index: &SemanticIndex,
+    ) -> Result<CodeReviewResult> {
+        let related = index
+            .retrieve_related(self, &context.source_code, &context.file_path, RELATED_CHUNKS_TOP_K)
+            .await?;
+
+        let mut prompt = self.build_review_prompt(context);
+        if !related.is_empty() {
+            prompt.push_str(&Self::build_related_code_section(&related));
+        }
+
+        let prompt_tokens = chunking::count_tokens(&prompt);
+        let issues = match self.complete(prompt).await {
+            Ok(outcome) => self.extract_issues(outcome, context)?,
+            Err(e) => {
+                eprintln!("Warning: AI analysis unavailable: {}", e);
+                Vec::new()
+            }
         };
 
-        let response = self
-            .client
-            .post(format!("{}/messages", self.config.api_base_url))
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| CodeSageError::AIError(format!("API request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(CodeSageError::AIError(format!(
-                "API error {}: {}",
-                status, error_text
-            )));
+        Ok(CodeReviewResult {
+            file_path: context.file_path.clone(),
+            issues,
+            metrics: CodeMetrics {
+                lines_of_code: context.source_code.lines().count(),
+                cyclomatic_complexity: 0,
+                cognitive_complexity: 0,
+                maintainability_index: 0.0,
+                test_coverage: None,
+                duplication_percentage: 0.0,
+                technical_debt_minutes: 0,
+                abc_size: 0.0,
+                halstead_volume: 0.0,
+                halstead_difficulty: 0.0,
+                halstead_effort: 0.0,
+            },
+            suggestions: Vec::new(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            prompt_tokens,
+        })
+    }
+
+    /// Render retrieved chunks as a "Related code" prompt section.
+    fn build_related_code_section(related: &[RetrievedChunk]) -> String {
+        let mut section = String::from("\n\nRelated code from this project (for context only, do not review it directly):\n");
+        for chunk in related {
+            section.push_str(&format!(
+                "\n--- {} (lines {}-{}) ---\n{}\n",
+                chunk.file_path.display(),
+                chunk.start_line,
+                chunk.end_line,
+                chunk.content
+            ));
         }
+        section
+    }
 
-        let claude_response: ClaudeResponse = response
-            .json()
-            .await
-            .map_err(|e| CodeSageError::AIError(format!("Failed to parse response: {}", e)))?;
-
-        Ok(claude_response
-            .content
-            .first()
-            .map(|c| c.text.clone())
-            .unwrap_or_default())
-    }
-
-    /// Parse AI response into issues (simplified)
-    fn parse_ai_response(&self, response: &str, context: &AnalysisContext) -> Vec<Issue> {
-        let mut issues = Vec::new();
-
-        // Simple keyword-based parsing
-        // In a real implementation, this would use structured output from the AI
-
-        if response.to_lowercase().contains("security")
-            || response.to_lowercase().contains("vulnerability")
-        {
-            issues.push(Issue {
-                id: "AI_SECURITY001".to_string(),
-                severity: Severity::P1,
-                category: IssueCategory::Security,
-                location: Location {
-                    file_path: context.file_path.clone(),
-                    start_line: 1,
-                    start_column: 1,
-                    end_line: context.source_code.lines().count(),
-                    end_column: 1,
+    /// Describe the `report_issues` tool so the model returns structured data
+    /// instead of prose we would otherwise have to scrape for keywords.
+    fn report_issues_tool() -> ToolSpec {
+        ToolSpec {
+            name: REPORT_ISSUES_TOOL.to_string(),
+            description: "Report the code review issues found in the supplied file.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "issues": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "id": { "type": "string" },
+                                "severity": {
+                                    "type": "string",
+                                    "enum": ["P0", "P1", "P2", "P3"]
+                                },
+                                "category": {
+                                    "type": "string",
+                                    "enum": [
+                                        "Bug", "Security", "Performance", "Maintainability",
+                                        "Style", "Documentation", "TestCoverage"
+                                    ]
+                                },
+                                "start_line": { "type": "integer", "minimum": 1 },
+                                "end_line": { "type": "integer", "minimum": 1 },
+                                "message": { "type": "string" },
+                                "explanation": { "type": "string" },
+                                "fix_suggestion": {
+                                    "type": ["object", "null"],
+                                    "properties": {
+                                        "description": { "type": "string" },
+                                        "diff": { "type": "string" },
+                                        "safe_to_auto_apply": { "type": "boolean" }
+                                    },
+                                    "required": ["description", "diff", "safe_to_auto_apply"]
+                                },
+                                "confidence": { "type": "number", "minimum": 0, "maximum": 1 }
+                            },
+                            "required": [
+                                "id", "severity", "category", "start_line", "end_line",
+                                "message", "explanation", "confidence"
+                            ]
+                        }
+                    }
                 },
-                message: "Potential security concern identified by AI".to_string(),
-                explanation: response.to_string(),
-                fix_suggestion: None,
-                confidence: 0.75,
-            });
+                "required": ["issues"]
+            }),
         }
+    }
 
-        if response.to_lowercase().contains("bug") || response.to_lowercase().contains("error") {
-            issues.push(Issue {
-                id: "AI_BUG001".to_string(),
-                severity: Severity::P2,
-                category: IssueCategory::Bug,
-                location: Location {
-                    file_path: context.file_path.clone(),
-                    start_line: 1,
-                    start_column: 1,
-                    end_line: context.source_code.lines().count(),
-                    end_column: 1,
+    /// Ask the configured provider to complete `prompt`, forced through the
+    /// `report_issues` tool.
+    async fn complete(&self, prompt: String) -> Result<CompletionOutcome> {
+        self.provider.complete(prompt, &Self::report_issues_tool()).await
+    }
+
+    /// Extract the structured issues reported via the `report_issues` tool call
+    fn extract_issues(&self, outcome: CompletionOutcome, context: &AnalysisContext) -> Result<Vec<Issue>> {
+        let input = match outcome {
+            CompletionOutcome::ToolInput(input) => input,
+            // tool_choice forces the tool, but if the model still replied with
+            // plain text, treat it as "nothing to report" rather than failing.
+            CompletionOutcome::Text(_) => return Ok(Vec::new()),
+        };
+
+        let parsed: ReportIssuesInput = serde_json::from_value(input).map_err(|e| {
+            CodeSageError::AIError(format!("Malformed report_issues payload: {}", e))
+        })?;
+
+        Ok(parsed
+            .issues
+            .into_iter()
+            .map(|issue| issue.into_issue(context))
+            .collect())
+    }
+
+    /// Review a single chunk window and shift its (window-relative) issue
+    /// locations back into absolute file coordinates.
+    async fn review_window(&self, context: &AnalysisContext, window: SourceWindow) -> Vec<Issue> {
+        let prompt = self.build_review_prompt_for(context, &window.text);
+        let line_offset = window.start_line - 1;
+
+        match self.complete(prompt).await {
+            Ok(outcome) => match self.extract_issues(outcome, context) {
+                Ok(issues) => shift_issues(issues, line_offset),
+                Err(e) => {
+                    eprintln!(
+                        "Warning: AI analysis failed for chunk starting at line {}: {}",
+                        window.start_line, e
+                    );
+                    Vec::new()
+                }
+            },
+            Err(e) => {
+                eprintln!(
+                    "Warning: AI analysis unavailable for chunk starting at line {}: {}",
+                    window.start_line, e
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Split the file into overlapping windows that fit the configured
+    /// context window, review each (bounded concurrency), and merge the
+    /// results, deduplicating issues reported from the overlap regions.
+    async fn review_in_windows(&self, context: &AnalysisContext) -> Vec<Issue> {
+        let scaffold_tokens = chunking::count_tokens(&self.build_review_prompt_for(context, ""));
+        let window_budget = self
+            .config
+            .context_window_tokens
+            .saturating_sub(RESERVED_COMPLETION_TOKENS + scaffold_tokens);
+
+        let windows = chunking::split_into_windows(&context.source_code, window_budget);
+
+        let per_window: Vec<Vec<Issue>> = stream::iter(windows)
+            .map(|window| self.review_window(context, window))
+            .buffer_unordered(MAX_CONCURRENT_WINDOWS)
+            .collect()
+            .await;
+
+        let mut seen = HashSet::new();
+        let mut merged = Vec::new();
+        for issue in per_window.into_iter().flatten() {
+            if seen.insert((issue.id.clone(), issue.location.start_line)) {
+                merged.push(issue);
+            }
+        }
+        merged
+    }
+
+    /// Describe the `report_summary` tool so the model returns a plain
+    /// summary string instead of having to be scraped out of prose.
+    fn report_summary_tool() -> ToolSpec {
+        ToolSpec {
+            name: REPORT_SUMMARY_TOOL.to_string(),
+            description: "Report a cross-file summary of recurring patterns across the issues found."
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "summary": { "type": "string" }
                 },
-                message: "Potential bug identified by AI".to_string(),
-                explanation: response.to_string(),
-                fix_suggestion: None,
-                confidence: 0.7,
-            });
+                "required": ["summary"]
+            }),
+        }
+    }
+
+    /// Build the prompt for `summarize_findings`, listing up to
+    /// `MAX_SUMMARY_ISSUES` issues so the model can look for recurring
+    /// anti-patterns and themes across files rather than per-file issues.
+    fn build_summary_prompt(issues: &[Issue]) -> String {
+        let mut listing = String::new();
+        for issue in issues.iter().take(MAX_SUMMARY_ISSUES) {
+            listing.push_str(&format!(
+                "- {}:{} [{}] {} - {}\n",
+                issue.location.file_path.display(),
+                issue.location.start_line,
+                issue.id,
+                issue.message,
+                issue.explanation
+            ));
+        }
+        if issues.len() > MAX_SUMMARY_ISSUES {
+            listing.push_str(&format!(
+                "... and {} more issue(s) not shown\n",
+                issues.len() - MAX_SUMMARY_ISSUES
+            ));
         }
 
-        issues
+        format!(
+            r#"The following issues were found across a code review of multiple files:
+
+{listing}
+Identify recurring anti-patterns or themes that show up across several of
+these files (for example, the same kind of bug or missing validation
+repeated in different places). Report your findings as a short summary
+using the report_summary tool."#
+        )
     }
+
+    /// Ask the model for a cross-file summary of recurring patterns across
+    /// `issues`. Returns an empty string with no request made if there is
+    /// nothing to summarize.
+    pub async fn summarize_findings(&self, issues: &[Issue]) -> Result<String> {
+        if issues.is_empty() {
+            return Ok(String::new());
+        }
+
+        let prompt = Self::build_summary_prompt(issues);
+        match self.provider.complete(prompt, &Self::report_summary_tool()).await? {
+            CompletionOutcome::ToolInput(input) => {
+                let parsed: ReportSummaryInput = serde_json::from_value(input).map_err(|e| {
+                    CodeSageError::AIError(format!("Malformed report_summary payload: {}", e))
+                })?;
+                Ok(parsed.summary)
+            }
+            // Free text is still useful for a summary, unlike extract_issues'
+            // structured-or-nothing case.
+            CompletionOutcome::Text(text) => Ok(text),
+        }
+    }
+}
+
+/// Shift every issue's location by `line_offset` lines, turning a window's
+/// relative line numbers into absolute ones.
+fn shift_issues(mut issues: Vec<Issue>, line_offset: usize) -> Vec<Issue> {
+    for issue in &mut issues {
+        issue.location.start_line += line_offset;
+        issue.location.end_line += line_offset;
+    }
+    issues
 }
 
 impl Default for AIClient {
@@ -214,21 +460,23 @@ impl Default for AIClient {
 #[async_trait]
 impl AIReviewer for AIClient {
     async fn review(&self, context: &AnalysisContext) -> Result<CodeReviewResult> {
-        let prompt = self.build_review_prompt(context);
-
-        // Try to call the API, but provide a fallback for when API key is not available
-        let ai_response = match self.call_claude_api(prompt).await {
-            Ok(response) => response,
-            Err(e) => {
-                // Fallback to basic analysis when API is not available
-                eprintln!("Warning: AI analysis unavailable: {}", e);
-                "AI analysis unavailable. Please set ANTHROPIC_API_KEY environment variable."
-                    .to_string()
+        let full_prompt = self.build_review_prompt(context);
+        let prompt_tokens = chunking::count_tokens(&full_prompt);
+
+        let issues = if prompt_tokens + RESERVED_COMPLETION_TOKENS <= self.config.context_window_tokens {
+            // Fast path: the whole file fits in one request.
+            match self.complete(full_prompt).await {
+                Ok(outcome) => self.extract_issues(outcome, context)?,
+                Err(e) => {
+                    // Fallback to basic analysis when API is not available
+                    eprintln!("Warning: AI analysis unavailable: {}", e);
+                    Vec::new()
+                }
             }
+        } else {
+            self.review_in_windows(context).await
         };
 
-        let issues = self.parse_ai_response(&ai_response, context);
-
         Ok(CodeReviewResult {
             file_path: context.file_path.clone(),
             issues,
@@ -240,9 +488,14 @@ impl AIReviewer for AIClient {
                 test_coverage: None,
                 duplication_percentage: 0.0,
                 technical_debt_minutes: 0,
+                abc_size: 0.0,
+                halstead_volume: 0.0,
+                halstead_difficulty: 0.0,
+                halstead_effort: 0.0,
             },
             suggestions: Vec::new(),
             timestamp: chrono::Utc::now().to_rfc3339(),
+            prompt_tokens,
         })
     }
 }
@@ -0,0 +1,12 @@
+//! CodeSage AI Library
+//!
+//! AI-powered code review client
+
+pub mod chunking;
+pub mod client;
+pub mod provider;
+pub mod semantic_index;
+
+pub use client::{AIClient, AIConfig};
+pub use provider::{LlmProvider, Provider};
+pub use semantic_index::{RetrievedChunk, SemanticIndex};
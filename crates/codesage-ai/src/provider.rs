@@ -0,0 +1,519 @@
+//! Multi-provider LLM transport
+//!
+//! `AIClient` talks to whichever backend `AIConfig::provider` selects through
+//! this trait, so the review flow in `client.rs` never has to know whether
+//! it's talking to Anthropic, OpenAI, or an OpenAI-compatible local server.
+
+use crate::AIConfig;
+use async_trait::async_trait;
+use codesage_core::{CodeSageError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Which LLM backend `AIClient` should dispatch to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// Anthropic's Messages API (the default).
+    Anthropic,
+    /// OpenAI's Chat Completions API.
+    OpenAi,
+    /// Any server exposing an OpenAI-compatible Chat Completions API (local
+    /// model runners, self-hosted gateways, etc.). Unlike `OpenAi`, the API
+    /// key is optional.
+    OpenAiCompatible,
+}
+
+/// Description of the single tool a review prompt forces the model to call,
+/// independent of how each provider encodes tool/function calling on the wire.
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+/// Result of a forced tool-call completion.
+pub enum CompletionOutcome {
+    /// The model called the requested tool; this is its `input`/`arguments`.
+    ToolInput(serde_json::Value),
+    /// The model replied with plain text instead (no tool call was made).
+    Text(String),
+}
+
+/// Transport for a single LLM backend: a forced-tool completion plus
+/// embeddings, so `AIClient` can be generic over the wire format.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Send `prompt`, forcing the model to respond via `tool`.
+    async fn complete(&self, prompt: String, tool: &ToolSpec) -> Result<CompletionOutcome>;
+
+    /// Embed `text`, returning a dense vector.
+    async fn embed(&self, text: &str) -> Result<Vec<f32>>;
+}
+
+/// Build the provider selected by `config.provider`.
+pub fn build_provider(config: &AIConfig) -> Box<dyn LlmProvider> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+        .build()
+        .expect("Failed to create HTTP client");
+
+    match config.provider {
+        Provider::Anthropic => Box::new(AnthropicProvider {
+            client,
+            config: config.clone(),
+        }),
+        Provider::OpenAi => Box::new(OpenAiProvider {
+            client,
+            config: config.clone(),
+        }),
+        Provider::OpenAiCompatible => Box::new(OpenAiCompatibleProvider {
+            client,
+            config: config.clone(),
+        }),
+    }
+}
+
+// ============================================================================
+// Anthropic
+// ============================================================================
+
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    config: AIConfig,
+}
+
+#[derive(Serialize)]
+struct ClaudeRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<ClaudeMessage>,
+    tools: Vec<ClaudeTool>,
+    tool_choice: ClaudeToolChoice,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ClaudeMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct ClaudeTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct ClaudeToolChoice {
+    #[serde(rename = "type")]
+    choice_type: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ClaudeResponse {
+    content: Vec<ClaudeContentBlock>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClaudeContentBlock {
+    Text {
+        #[allow(dead_code)]
+        text: String,
+    },
+    ToolUse {
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize)]
+struct ClaudeEmbeddingRequest {
+    model: String,
+    input: String,
+}
+
+#[derive(Deserialize)]
+struct ClaudeEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    async fn complete(&self, prompt: String, tool: &ToolSpec) -> Result<CompletionOutcome> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| CodeSageError::AIError("ANTHROPIC_API_KEY not set".to_string()))?;
+
+        let request = ClaudeRequest {
+            model: self.config.model.clone(),
+            max_tokens: 4096,
+            messages: vec![ClaudeMessage {
+                role: "user".to_string(),
+                content: prompt,
+            }],
+            tools: vec![ClaudeTool {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                input_schema: tool.input_schema.clone(),
+            }],
+            tool_choice: ClaudeToolChoice {
+                choice_type: "tool".to_string(),
+                name: tool.name.clone(),
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/messages", self.config.api_base_url))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| CodeSageError::AIError(format!("API request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(CodeSageError::AIError(format!(
+                "API error {}: {}",
+                status, error_text
+            )));
+        }
+
+        let parsed: ClaudeResponse = response
+            .json()
+            .await
+            .map_err(|e| CodeSageError::AIError(format!("Failed to parse response: {}", e)))?;
+
+        // The model may emit a text block alongside the tool_use block; only
+        // the tool call carries the structured data we asked for.
+        let tool_use = parsed.content.into_iter().find_map(|block| match block {
+            ClaudeContentBlock::ToolUse { name, input } if name == tool.name => Some(input),
+            _ => None,
+        });
+
+        Ok(match tool_use {
+            Some(input) => CompletionOutcome::ToolInput(input),
+            None => CompletionOutcome::Text(String::new()),
+        })
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| CodeSageError::AIError("ANTHROPIC_API_KEY not set".to_string()))?;
+
+        let request = ClaudeEmbeddingRequest {
+            model: self.config.model.clone(),
+            input: text.to_string(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/embeddings", self.config.api_base_url))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| CodeSageError::AIError(format!("Embedding request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(CodeSageError::AIError(format!(
+                "Embedding API error {}",
+                response.status()
+            )));
+        }
+
+        let parsed: ClaudeEmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| CodeSageError::AIError(format!("Failed to parse embedding: {}", e)))?;
+
+        Ok(parsed.embedding)
+    }
+}
+
+// ============================================================================
+// OpenAI / OpenAI-compatible
+// ============================================================================
+
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    config: AIConfig,
+}
+
+pub struct OpenAiCompatibleProvider {
+    client: reqwest::Client,
+    config: AIConfig,
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    async fn complete(&self, prompt: String, tool: &ToolSpec) -> Result<CompletionOutcome> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| CodeSageError::AIError("OPENAI_API_KEY not set".to_string()))?;
+        openai_chat_complete(
+            &self.client,
+            &self.config.api_base_url,
+            Some(api_key),
+            &self.config.model,
+            prompt,
+            tool,
+        )
+        .await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| CodeSageError::AIError("OPENAI_API_KEY not set".to_string()))?;
+        openai_embed(
+            &self.client,
+            &self.config.api_base_url,
+            Some(api_key),
+            &self.config.model,
+            text,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    async fn complete(&self, prompt: String, tool: &ToolSpec) -> Result<CompletionOutcome> {
+        openai_chat_complete(
+            &self.client,
+            &self.config.api_base_url,
+            self.config.api_key.as_deref(),
+            &self.config.model,
+            prompt,
+            tool,
+        )
+        .await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        openai_embed(
+            &self.client,
+            &self.config.api_base_url,
+            self.config.api_key.as_deref(),
+            &self.config.model,
+            text,
+        )
+        .await
+    }
+}
+
+#[derive(Serialize)]
+struct OpenAiChatRequest {
+    model: String,
+    max_tokens: u32,
+    messages: Vec<OpenAiMessage>,
+    tools: Vec<OpenAiTool>,
+    tool_choice: OpenAiToolChoice,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OpenAiMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct OpenAiTool {
+    #[serde(rename = "type")]
+    kind: String,
+    function: OpenAiFunction,
+}
+
+#[derive(Serialize)]
+struct OpenAiFunction {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct OpenAiToolChoice {
+    #[serde(rename = "type")]
+    kind: String,
+    function: OpenAiToolChoiceFunction,
+}
+
+#[derive(Serialize)]
+struct OpenAiToolChoiceFunction {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChatResponse {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponseMessage {
+    content: Option<String>,
+    tool_calls: Option<Vec<OpenAiToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiToolCall {
+    function: OpenAiToolCallFunction,
+}
+
+#[derive(Deserialize)]
+struct OpenAiToolCallFunction {
+    name: String,
+    arguments: String,
+}
+
+#[derive(Serialize)]
+struct OpenAiEmbeddingRequest {
+    model: String,
+    input: String,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbeddingDatum>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingDatum {
+    embedding: Vec<f32>,
+}
+
+async fn openai_chat_complete(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    prompt: String,
+    tool: &ToolSpec,
+) -> Result<CompletionOutcome> {
+    let request = OpenAiChatRequest {
+        model: model.to_string(),
+        max_tokens: 4096,
+        messages: vec![OpenAiMessage {
+            role: "user".to_string(),
+            content: prompt,
+        }],
+        tools: vec![OpenAiTool {
+            kind: "function".to_string(),
+            function: OpenAiFunction {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.input_schema.clone(),
+            },
+        }],
+        tool_choice: OpenAiToolChoice {
+            kind: "function".to_string(),
+            function: OpenAiToolChoiceFunction {
+                name: tool.name.clone(),
+            },
+        },
+    };
+
+    let mut builder = client.post(format!("{}/chat/completions", base_url)).json(&request);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| CodeSageError::AIError(format!("API request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(CodeSageError::AIError(format!(
+            "API error {}: {}",
+            status, error_text
+        )));
+    }
+
+    let parsed: OpenAiChatResponse = response
+        .json()
+        .await
+        .map_err(|e| CodeSageError::AIError(format!("Failed to parse response: {}", e)))?;
+
+    let Some(message) = parsed.choices.into_iter().next().map(|c| c.message) else {
+        return Ok(CompletionOutcome::Text(String::new()));
+    };
+
+    if let Some(calls) = message.tool_calls {
+        if let Some(call) = calls.into_iter().find(|c| c.function.name == tool.name) {
+            let input: serde_json::Value = serde_json::from_str(&call.function.arguments)
+                .map_err(|e| CodeSageError::AIError(format!("Malformed tool arguments: {}", e)))?;
+            return Ok(CompletionOutcome::ToolInput(input));
+        }
+    }
+
+    Ok(CompletionOutcome::Text(message.content.unwrap_or_default()))
+}
+
+async fn openai_embed(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: Option<&str>,
+    model: &str,
+    text: &str,
+) -> Result<Vec<f32>> {
+    let request = OpenAiEmbeddingRequest {
+        model: model.to_string(),
+        input: text.to_string(),
+    };
+
+    let mut builder = client.post(format!("{}/embeddings", base_url)).json(&request);
+    if let Some(key) = api_key {
+        builder = builder.header("Authorization", format!("Bearer {}", key));
+    }
+
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| CodeSageError::AIError(format!("Embedding request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(CodeSageError::AIError(format!(
+            "Embedding API error {}",
+            response.status()
+        )));
+    }
+
+    let mut parsed: OpenAiEmbeddingResponse = response
+        .json()
+        .await
+        .map_err(|e| CodeSageError::AIError(format!("Failed to parse embedding: {}", e)))?;
+
+    Ok(parsed.data.pop().map(|d| d.embedding).unwrap_or_default())
+}
@@ -0,0 +1,325 @@
+//! Semantic code index for retrieval-augmented review
+//!
+//! Splits files into function/impl-level chunks, embeds them via the
+//! project's configured `AIClient`, and persists `(file, chunk_span,
+//! vector)` rows in a local SQLite database so a review of one file can
+//! pull in the most relevant snippets from the rest of the project as
+//! extra context.
+
+use crate::client::AIClient;
+use codesage_core::{CodeSageError, Result};
+use ordered_float::OrderedFloat;
+use rusqlite::{params, Connection};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// A function/impl-level slice of a source file.
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    content: String,
+}
+
+/// A chunk retrieved from the corpus as relevant context for a review.
+pub struct RetrievedChunk {
+    pub file_path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+    pub similarity: f32,
+}
+
+/// Local, file-backed semantic index of a project's source chunks.
+pub struct SemanticIndex {
+    conn: Connection,
+}
+
+impl SemanticIndex {
+    /// Open (creating if necessary) the index database at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)
+            .map_err(|e| CodeSageError::AnalysisError(format!("Failed to open index: {}", e)))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chunks (
+                file_path    TEXT NOT NULL,
+                start_line   INTEGER NOT NULL,
+                end_line     INTEGER NOT NULL,
+                content      TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                vector       BLOB NOT NULL,
+                UNIQUE(file_path, start_line, end_line)
+            )",
+            [],
+        )
+        .map_err(|e| CodeSageError::AnalysisError(format!("Failed to create schema: {}", e)))?;
+
+        Ok(Self { conn })
+    }
+
+    /// (Re-)index `source` under `file_path`. Chunks whose content hash is
+    /// unchanged from the stored row are skipped. Returns the number of
+    /// chunks that were embedded (inserted or updated).
+    pub async fn index_file(&self, ai_client: &AIClient, file_path: &Path, source: &str) -> Result<usize> {
+        let file_key = file_path.display().to_string();
+        let mut reembedded = 0usize;
+
+        for chunk in split_into_chunks(source) {
+            let hash = content_hash(&chunk.content);
+
+            let existing_hash: Option<String> = self
+                .conn
+                .query_row(
+                    "SELECT content_hash FROM chunks WHERE file_path = ?1 AND start_line = ?2 AND end_line = ?3",
+                    params![file_key, chunk.start_line as i64, chunk.end_line as i64],
+                    |row| row.get(0),
+                )
+                .ok();
+
+            if existing_hash.as_deref() == Some(hash.as_str()) {
+                continue;
+            }
+
+            let vector = normalized_embedding(ai_client, &chunk.content).await?;
+            self.conn
+                .execute(
+                    "INSERT INTO chunks (file_path, start_line, end_line, content, content_hash, vector)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT(file_path, start_line, end_line)
+                     DO UPDATE SET content = excluded.content,
+                                   content_hash = excluded.content_hash,
+                                   vector = excluded.vector",
+                    params![
+                        file_key,
+                        chunk.start_line as i64,
+                        chunk.end_line as i64,
+                        chunk.content,
+                        hash,
+                        vector_to_bytes(&vector),
+                    ],
+                )
+                .map_err(|e| CodeSageError::AnalysisError(format!("Failed to index chunk: {}", e)))?;
+
+            reembedded += 1;
+        }
+
+        Ok(reembedded)
+    }
+
+    /// Retrieve the top `k` chunks (excluding `exclude_file`) most similar to
+    /// `source`, for use as "Related code" context in a review prompt.
+    pub async fn retrieve_related(
+        &self,
+        ai_client: &AIClient,
+        source: &str,
+        exclude_file: &Path,
+        k: usize,
+    ) -> Result<Vec<RetrievedChunk>> {
+        let exclude_key = exclude_file.display().to_string();
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT file_path, start_line, end_line, content, vector FROM chunks WHERE file_path != ?1")
+            .map_err(|e| CodeSageError::AnalysisError(format!("Failed to query index: {}", e)))?;
+
+        let rows = stmt
+            .query_map(params![exclude_key], |row| {
+                let file_path: String = row.get(0)?;
+                let start_line: i64 = row.get(1)?;
+                let end_line: i64 = row.get(2)?;
+                let content: String = row.get(3)?;
+                let vector: Vec<u8> = row.get(4)?;
+                Ok((file_path, start_line, end_line, content, vector))
+            })
+            .map_err(|e| CodeSageError::AnalysisError(format!("Failed to read index rows: {}", e)))?;
+
+        let mut candidates = Vec::new();
+        for row in rows {
+            let (file_path, start_line, end_line, content, vector_bytes) = row
+                .map_err(|e| CodeSageError::AnalysisError(format!("Failed to decode row: {}", e)))?;
+            candidates.push((file_path, start_line, end_line, content, bytes_to_vector(&vector_bytes)));
+        }
+
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = normalized_embedding(ai_client, source).await?;
+        let similarities = cosine_similarities(&query, candidates.iter().map(|c| &c.4));
+
+        // A candidate whose stored vector has the wrong dimension (e.g. the
+        // index was built with a different embedding model before
+        // `codesage.toml`/`--model` changed) comes back as `None` from
+        // `cosine_similarities` rather than a score; drop it instead of
+        // surfacing a meaningless comparison.
+        let mut scored: Vec<RetrievedChunk> = candidates
+            .into_iter()
+            .zip(similarities)
+            .filter_map(|((file_path, start_line, end_line, content, _), similarity)| {
+                similarity.map(|similarity| RetrievedChunk {
+                    file_path: PathBuf::from(file_path),
+                    start_line: start_line as usize,
+                    end_line: end_line as usize,
+                    content,
+                    similarity,
+                })
+            })
+            .collect();
+
+        // `ordered_float` gives us a total order even if an embedding ever
+        // produces NaN (e.g. from an all-zero vector before normalizing).
+        scored.sort_by_key(|c| std::cmp::Reverse(OrderedFloat(c.similarity)));
+        scored.truncate(k);
+        Ok(scored)
+    }
+}
+
+/// Split source into coarse function/impl-level chunks. Uses the same
+/// line-scan style as `MetricsAnalyzer` rather than a real parse, matching
+/// the rest of this crate's text-based heuristics.
+fn split_into_chunks(source: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut chunks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        if trimmed.starts_with("fn ")
+            || trimmed.starts_with("pub fn ")
+            || trimmed.starts_with("async fn ")
+            || trimmed.starts_with("impl ")
+            || trimmed.starts_with("pub async fn ")
+        {
+            let start = i;
+            let mut depth = 0i32;
+            let mut seen_brace = false;
+            let mut end = i;
+
+            for (offset, line) in lines[i..].iter().enumerate() {
+                depth += line.matches('{').count() as i32;
+                depth -= line.matches('}').count() as i32;
+                if line.contains('{') {
+                    seen_brace = true;
+                }
+                end = i + offset;
+                if seen_brace && depth <= 0 {
+                    break;
+                }
+            }
+
+            chunks.push(Chunk {
+                start_line: start + 1,
+                end_line: end + 1,
+                content: lines[start..=end].join("\n"),
+            });
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    chunks
+}
+
+/// Stable content hash used to detect unchanged chunks between index runs.
+fn content_hash(content: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Embed `text` via `ai_client`'s configured provider and L2-normalize the
+/// result, so `cosine_similarities` can treat every stored vector as already
+/// normalized and skip re-normalizing on every comparison.
+async fn normalized_embedding(ai_client: &AIClient, text: &str) -> Result<Vec<f32>> {
+    let mut vector = ai_client.embed(text).await?;
+
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in &mut vector {
+            *v /= norm;
+        }
+    }
+    Ok(vector)
+}
+
+/// Compute cosine similarity between `query` and each candidate vector,
+/// batched as a matrix-vector product via `matrixmultiply` instead of one
+/// dot product at a time.
+///
+/// The index persists across runs and the embedding model/provider can
+/// change via `codesage.toml`/`--model`, so a stored vector is not
+/// guaranteed to have `query.len()` elements (a stale row from a different
+/// model, or one `bytes_to_vector` truncated from an odd-length BLOB). The
+/// `sgemm` call below assumes every row is exactly `dims` long, so any
+/// candidate that doesn't match is excluded from `matrix` before the
+/// `unsafe` call and comes back as `None` here rather than risking an
+/// out-of-bounds read.
+fn cosine_similarities<'a>(
+    query: &[f32],
+    candidates: impl ExactSizeIterator<Item = &'a Vec<f32>>,
+) -> Vec<Option<f32>> {
+    let n_total = candidates.len();
+    let dims = query.len();
+    if n_total == 0 {
+        return Vec::new();
+    }
+
+    let mut matrix = Vec::with_capacity(n_total * dims);
+    let mut valid_indices = Vec::with_capacity(n_total);
+    for (i, vector) in candidates.enumerate() {
+        if vector.len() == dims {
+            valid_indices.push(i);
+            matrix.extend_from_slice(vector);
+        }
+    }
+
+    let mut result = vec![None; n_total];
+    let n = valid_indices.len();
+    if n == 0 {
+        return result;
+    }
+
+    let mut packed = vec![0f32; n];
+    unsafe {
+        matrixmultiply::sgemm(
+            n,
+            dims,
+            1,
+            1.0,
+            matrix.as_ptr(),
+            dims as isize,
+            1,
+            query.as_ptr(),
+            1,
+            dims as isize,
+            0.0,
+            packed.as_mut_ptr(),
+            1,
+            1,
+        );
+    }
+
+    // Both the query and stored vectors are already L2-normalized, so the
+    // raw dot product above is the cosine similarity.
+    for (slot, orig_idx) in valid_indices.into_iter().enumerate() {
+        result[orig_idx] = Some(packed[slot]);
+    }
+    result
+}
+
+fn vector_to_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+fn bytes_to_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
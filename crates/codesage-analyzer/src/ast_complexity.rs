@@ -0,0 +1,254 @@
+//! AST-driven cyclomatic and cognitive complexity, computed by walking the
+//! real tree-sitter tree `codesage_parser::CodeParser` produces for a
+//! registered grammar, instead of `crate::cognitive`/`MetricsAnalyzer`'s
+//! text heuristics. A `string_literal` or `comment` node is never mistaken
+//! for a decision point this way, unlike the text scan (see
+//! `tests/edge_case_tests.rs`'s "special characters"/"very long line"
+//! cases), so `MetricsAnalyzer::analyze` prefers this path whenever a tree
+//! is available and only falls back to the heuristic otherwise.
+
+use tree_sitter::Node;
+
+/// A `fn`/method definition found while walking the tree, paired with the
+/// 1-based line span `MetricsAnalyzer` should report issues against.
+/// Nested closures and inner `fn`s aren't collected separately, matching
+/// `crate::functions::extract_functions`'s text-based behavior.
+pub(crate) struct AstFunction<'a> {
+    pub node: Node<'a>,
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Every top-level `fn`/method definition in `root`, in source order.
+pub(crate) fn functions_in_tree<'a>(root: Node<'a>, source: &[u8]) -> Vec<AstFunction<'a>> {
+    let mut out = Vec::new();
+    collect_functions(root, source, &mut out);
+    out
+}
+
+fn collect_functions<'a>(node: Node<'a>, source: &[u8], out: &mut Vec<AstFunction<'a>>) {
+    if node.kind() == "function_item" {
+        let name = node
+            .child_by_field_name("name")
+            .and_then(|n| n.utf8_text(source).ok())
+            .unwrap_or("")
+            .to_string();
+        out.push(AstFunction {
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            name,
+            node,
+        });
+        return; // don't descend into nested fns/closures
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_functions(child, source, out);
+    }
+}
+
+/// Cyclomatic complexity of the subtree rooted at `node`: `1 +` one per
+/// decision point - an `if`/`while`/`for`/`loop`, each `match` arm, each
+/// `&&`/`||` operator, and each `?` (`try_expression`).
+pub(crate) fn cyclomatic_complexity(node: Node) -> u32 {
+    1 + count_decision_points(node)
+}
+
+fn count_decision_points(node: Node) -> u32 {
+    let mut count = match node.kind() {
+        "if_expression" | "while_expression" | "for_expression" | "loop_expression"
+        | "match_arm" | "try_expression" => 1,
+        "binary_expression" if is_logical_operator(node) => 1,
+        _ => 0,
+    };
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        count += count_decision_points(child);
+    }
+    count
+}
+
+/// Cognitive complexity of the subtree rooted at `node` (typically a
+/// `function_item` or its `block` body), following SonarSource's
+/// nesting-aware rule: an `if`/`while`/`for`/`loop`/`match` each add
+/// `1 + nesting` and increment `nesting` for their own body; an
+/// `else`/`else if` chain adds a flat `1` per link instead (no nesting
+/// penalty); each maximal run of the same boolean operator (`&&`/`||`)
+/// adds `1`; a labeled `break`/`continue` adds `1`; a direct call back to
+/// `fn_name` (the enclosing function's own name) adds `1`, matching
+/// `crate::cognitive::function_cognitive_complexity`'s recursion rule.
+/// Pass `""` for `fn_name` if there's no enclosing function to recurse
+/// into (e.g. scoring a whole file with no extracted functions).
+pub(crate) fn cognitive_complexity(node: Node, fn_name: &str, source: &[u8]) -> u32 {
+    let mut score = 0;
+    walk_cognitive(node, 0, fn_name, source, &mut score);
+    score
+}
+
+fn walk_cognitive(node: Node, nesting: u32, fn_name: &str, source: &[u8], score: &mut u32) {
+    match node.kind() {
+        "if_expression" => walk_if_chain(node, nesting, fn_name, source, score),
+        "while_expression" | "for_expression" | "loop_expression" => {
+            *score += 1 + nesting;
+            walk_children_with_nested_body(node, nesting, "block", fn_name, source, score);
+        }
+        "match_expression" => {
+            *score += 1 + nesting;
+            walk_children_with_nested_body(node, nesting, "match_block", fn_name, source, score);
+        }
+        "binary_expression" => {
+            if is_logical_operator(node) && !is_nested_in_logical_chain(node) {
+                *score += count_operator_runs(node);
+            }
+            walk_children(node, nesting, fn_name, source, score);
+        }
+        "break_expression" | "continue_expression" => {
+            if has_label(node) {
+                *score += 1;
+            }
+            walk_children(node, nesting, fn_name, source, score);
+        }
+        "call_expression" => {
+            if is_recursive_call(node, fn_name, source) {
+                *score += 1;
+            }
+            walk_children(node, nesting, fn_name, source, score);
+        }
+        _ => walk_children(node, nesting, fn_name, source, score),
+    }
+}
+
+/// Recurse into every child at `nesting`, except a direct child of kind
+/// `nested_body_kind` (the loop/match's own body), which is visited at
+/// `nesting + 1`.
+fn walk_children_with_nested_body(
+    node: Node,
+    nesting: u32,
+    nested_body_kind: &str,
+    fn_name: &str,
+    source: &[u8],
+    score: &mut u32,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == nested_body_kind {
+            walk_cognitive(child, nesting + 1, fn_name, source, score);
+        } else {
+            walk_cognitive(child, nesting, fn_name, source, score);
+        }
+    }
+}
+
+fn walk_children(node: Node, nesting: u32, fn_name: &str, source: &[u8], score: &mut u32) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk_cognitive(child, nesting, fn_name, source, score);
+    }
+}
+
+/// Walk an `if`/`else if`/`else` chain: the first `if` adds `1 + nesting`,
+/// every later link (`else if` or plain `else`) adds a flat `1`, and each
+/// link's own body is visited one nesting level deeper than the chain
+/// itself.
+fn walk_if_chain(if_node: Node, nesting: u32, fn_name: &str, source: &[u8], score: &mut u32) {
+    *score += 1 + nesting;
+    if let Some(condition) = if_node.child_by_field_name("condition") {
+        walk_cognitive(condition, nesting, fn_name, source, score);
+    }
+    if let Some(consequence) = if_node.child_by_field_name("consequence") {
+        walk_cognitive(consequence, nesting + 1, fn_name, source, score);
+    }
+
+    let mut alternative = if_node.child_by_field_name("alternative");
+    while let Some(else_clause) = alternative {
+        alternative = None;
+
+        let mut cursor = else_clause.walk();
+        for child in else_clause.children(&mut cursor) {
+            match child.kind() {
+                "if_expression" => {
+                    *score += 1; // else if: flat, no nesting multiplier
+                    if let Some(condition) = child.child_by_field_name("condition") {
+                        walk_cognitive(condition, nesting, fn_name, source, score);
+                    }
+                    if let Some(consequence) = child.child_by_field_name("consequence") {
+                        walk_cognitive(consequence, nesting + 1, fn_name, source, score);
+                    }
+                    alternative = child.child_by_field_name("alternative");
+                    break;
+                }
+                "block" => {
+                    *score += 1; // plain else: flat
+                    walk_cognitive(child, nesting + 1, fn_name, source, score);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether `node` (a `call_expression`) calls `fn_name` directly, e.g.
+/// `fn_name(...)` - not a path- or field-qualified call like
+/// `Self::fn_name(...)` or `self.fn_name(...)`, matching the narrower case
+/// the text-based fallback's own substring check is aimed at.
+fn is_recursive_call(node: Node, fn_name: &str, source: &[u8]) -> bool {
+    if fn_name.is_empty() {
+        return false;
+    }
+    node.child_by_field_name("function")
+        .is_some_and(|f| f.kind() == "identifier" && f.utf8_text(source) == Ok(fn_name))
+}
+
+fn is_logical_operator(node: Node) -> bool {
+    matches!(
+        node.child_by_field_name("operator").map(|op| op.kind()),
+        Some("&&") | Some("||")
+    )
+}
+
+fn is_nested_in_logical_chain(node: Node) -> bool {
+    node.parent().is_some_and(is_logical_operator)
+}
+
+/// Count maximal runs of the same boolean operator across a chain of
+/// `&&`/`||` binary expressions, e.g. `a && b && c || d` is two runs
+/// (`&&`, `||`) and scores `2`, not `3`.
+fn count_operator_runs(root: Node) -> u32 {
+    let mut operators = Vec::new();
+    collect_operators(root, &mut operators);
+
+    let mut runs = 0;
+    let mut previous = None;
+    for op in operators {
+        if previous != Some(op) {
+            runs += 1;
+        }
+        previous = Some(op);
+    }
+    runs
+}
+
+/// In-order list of `&&`/`||` operator tokens in a chain rooted at `node`,
+/// stopping at any operand that isn't itself a logical `binary_expression`.
+fn collect_operators<'a>(node: Node<'a>, out: &mut Vec<&'a str>) {
+    if !is_logical_operator(node) {
+        return;
+    }
+    if let Some(left) = node.child_by_field_name("left") {
+        collect_operators(left, out);
+    }
+    if let Some(op) = node.child_by_field_name("operator") {
+        out.push(op.kind());
+    }
+    if let Some(right) = node.child_by_field_name("right") {
+        collect_operators(right, out);
+    }
+}
+
+fn has_label(node: Node) -> bool {
+    (0..node.child_count()).any(|i| node.child(i).is_some_and(|child| child.kind() == "label"))
+}
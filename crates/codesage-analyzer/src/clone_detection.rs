@@ -0,0 +1,344 @@
+//! Type-2 clone detection: copy-pasted code that differs only in renamed
+//! identifiers or changed literal values, which exact-line comparison can't
+//! see at all.
+//!
+//! Each source is tokenized (via `crate::lexer`) with identifiers
+//! collapsed to a single placeholder and literals normalized by kind
+//! (keywords and punctuation are kept as-is, since those carry the
+//! block's actual structure). A
+//! window of `WINDOW_SIZE` normalized tokens is then slid across the
+//! stream, hashing each window with a Rabin-Karp rolling hash so the whole
+//! token stream only needs to be scanned once. Windows that land in the
+//! same hash bucket are re-checked for genuine token-for-token equality
+//! (hash collisions and coincidental matches both need filtering out), and
+//! matching windows that overlap or are adjacent get merged into a single
+//! clone instance instead of being reported one window at a time.
+
+use crate::lexer::{self, RawTokenKind};
+use codesage_core::Location;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+/// Tokens per sliding window. Short enough to catch a duplicated function
+/// body, long enough that incidental similarity (a handful of shared
+/// keywords) doesn't register as a clone.
+const WINDOW_SIZE: usize = 50;
+
+/// Multiplier for the Rabin-Karp rolling hash. Arithmetic is done in
+/// wrapping `u64`, so this just needs to mix bits well, not be prime.
+const HASH_BASE: u64 = 1_000_003;
+
+/// A file (or any other named chunk of source) to scan for clones.
+pub struct ClonableSource {
+    pub file_path: PathBuf,
+    pub source_code: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum NormToken {
+    Ident,
+    IntLiteral,
+    FloatLiteral,
+    StringLiteral,
+    CharLiteral,
+    Keyword(&'static str),
+    Punct(String),
+}
+
+struct TokenizedSource {
+    file_path: PathBuf,
+    tokens: Vec<NormToken>,
+    /// 1-based source line each token starts on.
+    lines: Vec<usize>,
+}
+
+/// One instance of a detected clone: the file and line range of a single
+/// copy. A clone is reported as 2+ of these, one per copy found.
+#[derive(Debug, Clone)]
+pub struct CloneInstance {
+    pub file_path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+pub struct CloneDetectionResult {
+    /// Percentage (0-100) of tokens across all sources that participate in
+    /// at least one clone.
+    pub duplication_percentage: f32,
+    /// Each entry is one clone, reported as the locations of its copies.
+    pub clone_groups: Vec<Vec<CloneInstance>>,
+}
+
+/// Find Type-2 clones across one or more sources. A single source is a
+/// valid input (the common case: checking one file for internal
+/// copy-paste), but nothing here assumes only one.
+pub fn detect_clones(sources: &[ClonableSource]) -> CloneDetectionResult {
+    let tokenized: Vec<TokenizedSource> = sources.iter().map(tokenize).collect();
+
+    let token_ids: Vec<Vec<u64>> = tokenized
+        .iter()
+        .map(|t| t.tokens.iter().map(token_id).collect())
+        .collect();
+
+    // Bucket every window by its rolling hash.
+    let mut buckets: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    for (source_idx, ids) in token_ids.iter().enumerate() {
+        for (start, hash) in rolling_hashes(ids, WINDOW_SIZE).into_iter().enumerate() {
+            buckets.entry(hash).or_default().push((source_idx, start));
+        }
+    }
+
+    // Re-verify each bucket and collect confirmed matching pairs of window
+    // positions, keyed by which pair of sources they bridge.
+    let mut pairs: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    for positions in buckets.into_values() {
+        if positions.len() < 2 {
+            continue;
+        }
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                let (source_a, start_a) = positions[i];
+                let (source_b, start_b) = positions[j];
+                if windows_equal(&tokenized, source_a, start_a, source_b, start_b) {
+                    let key = (source_a.min(source_b), source_a.max(source_b));
+                    let (start_a, start_b) = if source_a <= source_b {
+                        (start_a, start_b)
+                    } else {
+                        (start_b, start_a)
+                    };
+                    pairs.entry(key).or_default().push((start_a, start_b));
+                }
+            }
+        }
+    }
+
+    let mut clone_groups = Vec::new();
+    let mut duplicated: Vec<Vec<bool>> = tokenized.iter().map(|t| vec![false; t.tokens.len()]).collect();
+
+    for ((source_a, source_b), mut matches) in pairs {
+        matches.sort_unstable();
+        matches.dedup();
+
+        let mut run_start: Option<(usize, usize)> = None;
+        let mut run_end = (0usize, 0usize);
+
+        let mut flush = |run_start: &mut Option<(usize, usize)>, run_end: (usize, usize)| {
+            if let Some((start_a, start_b)) = run_start.take() {
+                let end_a = run_end.0 + WINDOW_SIZE;
+                let end_b = run_end.1 + WINDOW_SIZE;
+                mark_duplicated(&mut duplicated[source_a], start_a, end_a);
+                mark_duplicated(&mut duplicated[source_b], start_b, end_b);
+                clone_groups.push(vec![
+                    clone_instance(&tokenized[source_a], start_a, end_a),
+                    clone_instance(&tokenized[source_b], start_b, end_b),
+                ]);
+            }
+        };
+
+        for (start_a, start_b) in matches {
+            match run_start {
+                Some(_) if start_a == run_end.0 + 1 && start_b == run_end.1 + 1 => {
+                    run_end = (start_a, start_b);
+                }
+                _ => {
+                    flush(&mut run_start, run_end);
+                    run_start = Some((start_a, start_b));
+                    run_end = (start_a, start_b);
+                }
+            }
+        }
+        flush(&mut run_start, run_end);
+    }
+
+    let total_tokens: usize = tokenized.iter().map(|t| t.tokens.len()).sum();
+    let duplicated_tokens: usize = duplicated.iter().map(|d| d.iter().filter(|&&b| b).count()).sum();
+    let duplication_percentage = if total_tokens == 0 {
+        0.0
+    } else {
+        (duplicated_tokens as f32 / total_tokens as f32) * 100.0
+    };
+
+    CloneDetectionResult {
+        duplication_percentage,
+        clone_groups: merge_overlapping_groups(clone_groups),
+    }
+}
+
+/// Merge clone groups that share an overlapping instance into one. With 3+
+/// copies of the same duplicated block, the pairwise bucket match above
+/// produces a separate 2-member group per pair of sources that both
+/// contain it (e.g. (A,B), (A,C), (B,C)) instead of one group spanning
+/// every copy; this unions any groups whose instances overlap so a block
+/// duplicated N times is reported as a single N-member group.
+fn merge_overlapping_groups(groups: Vec<Vec<CloneInstance>>) -> Vec<Vec<CloneInstance>> {
+    let mut parent: Vec<usize> = (0..groups.len()).collect();
+
+    fn find(parent: &mut [usize], x: usize) -> usize {
+        if parent[x] != x {
+            parent[x] = find(parent, parent[x]);
+        }
+        parent[x]
+    }
+
+    for i in 0..groups.len() {
+        for j in (i + 1)..groups.len() {
+            let overlaps = groups[i]
+                .iter()
+                .any(|a| groups[j].iter().any(|b| instances_overlap(a, b)));
+            if overlaps {
+                let root_i = find(&mut parent, i);
+                let root_j = find(&mut parent, j);
+                if root_i != root_j {
+                    parent[root_i] = root_j;
+                }
+            }
+        }
+    }
+
+    let mut merged: HashMap<usize, Vec<CloneInstance>> = HashMap::new();
+    for (i, group) in groups.into_iter().enumerate() {
+        let root = find(&mut parent, i);
+        merged.entry(root).or_default().extend(group);
+    }
+
+    merged.into_values().map(dedup_instances).collect()
+}
+
+fn instances_overlap(a: &CloneInstance, b: &CloneInstance) -> bool {
+    a.file_path == b.file_path && a.start_line <= b.end_line && b.start_line <= a.end_line
+}
+
+/// Collapse instances left over from merging groups: exact duplicates, and
+/// instances in the same file whose line ranges overlap, become a single
+/// instance spanning their union.
+fn dedup_instances(mut instances: Vec<CloneInstance>) -> Vec<CloneInstance> {
+    instances.sort_by(|a, b| {
+        a.file_path
+            .cmp(&b.file_path)
+            .then(a.start_line.cmp(&b.start_line))
+            .then(a.end_line.cmp(&b.end_line))
+    });
+    let mut merged: Vec<CloneInstance> = Vec::with_capacity(instances.len());
+    for instance in instances {
+        match merged.last_mut() {
+            Some(last) if instances_overlap(last, &instance) => {
+                last.end_line = last.end_line.max(instance.end_line);
+            }
+            _ => merged.push(instance),
+        }
+    }
+    merged
+}
+
+fn mark_duplicated(duplicated: &mut [bool], start: usize, end: usize) {
+    let end = end.min(duplicated.len());
+    for slot in duplicated.iter_mut().take(end).skip(start) {
+        *slot = true;
+    }
+}
+
+fn clone_instance(source: &TokenizedSource, start: usize, end: usize) -> CloneInstance {
+    let end = end.min(source.tokens.len());
+    CloneInstance {
+        file_path: source.file_path.clone(),
+        start_line: source.lines[start],
+        end_line: source.lines[end.saturating_sub(1).max(start)],
+    }
+}
+
+fn windows_equal(
+    tokenized: &[TokenizedSource],
+    source_a: usize,
+    start_a: usize,
+    source_b: usize,
+    start_b: usize,
+) -> bool {
+    let a = &tokenized[source_a].tokens[start_a..start_a + WINDOW_SIZE];
+    let b = &tokenized[source_b].tokens[start_b..start_b + WINDOW_SIZE];
+    a == b
+}
+
+/// `CloneDetectionResult::clone_groups` entries into plain `Location`s, for
+/// callers (like `MetricsAnalyzer`) that just want to populate an issue's
+/// `related_locations`.
+pub fn instances_to_locations(instances: &[CloneInstance]) -> Vec<Location> {
+    instances
+        .iter()
+        .map(|i| Location {
+            file_path: i.file_path.clone(),
+            start_line: i.start_line,
+            start_column: 1,
+            end_line: i.end_line,
+            end_column: 1,
+        })
+        .collect()
+}
+
+fn token_id(tok: &NormToken) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tok.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Rabin-Karp rolling hash of every `window`-sized slice of `ids`, in
+/// order; `hashes[i]` covers `ids[i..i+window]`.
+fn rolling_hashes(ids: &[u64], window: usize) -> Vec<u64> {
+    if ids.len() < window {
+        return Vec::new();
+    }
+
+    let mut high_order = 1u64;
+    for _ in 0..window.saturating_sub(1) {
+        high_order = high_order.wrapping_mul(HASH_BASE);
+    }
+
+    let mut hash = 0u64;
+    for &id in &ids[0..window] {
+        hash = hash.wrapping_mul(HASH_BASE).wrapping_add(id);
+    }
+
+    let mut hashes = Vec::with_capacity(ids.len() - window + 1);
+    hashes.push(hash);
+
+    for i in window..ids.len() {
+        let outgoing = ids[i - window];
+        hash = hash.wrapping_sub(outgoing.wrapping_mul(high_order));
+        hash = hash.wrapping_mul(HASH_BASE).wrapping_add(ids[i]);
+        hashes.push(hash);
+    }
+
+    hashes
+}
+
+fn tokenize(source: &ClonableSource) -> TokenizedSource {
+    let mut tokens = Vec::new();
+    let mut lines = Vec::new();
+
+    for raw in lexer::tokenize(&source.source_code) {
+        let norm = match raw.kind {
+            RawTokenKind::Ident => NormToken::Ident,
+            RawTokenKind::IntLiteral => NormToken::IntLiteral,
+            RawTokenKind::FloatLiteral => NormToken::FloatLiteral,
+            RawTokenKind::StringLiteral => NormToken::StringLiteral,
+            RawTokenKind::CharLiteral => NormToken::CharLiteral,
+            RawTokenKind::Keyword => {
+                let kw = lexer::KEYWORDS
+                    .iter()
+                    .find(|&&k| k == raw.text)
+                    .expect("RawTokenKind::Keyword text is always in lexer::KEYWORDS");
+                NormToken::Keyword(kw)
+            }
+            RawTokenKind::Punct => NormToken::Punct(raw.text),
+        };
+        tokens.push(norm);
+        lines.push(raw.line);
+    }
+
+    TokenizedSource {
+        file_path: source.file_path.clone(),
+        tokens,
+        lines,
+    }
+}
@@ -0,0 +1,274 @@
+//! SonarSource-style cognitive complexity.
+//!
+//! `CodeParser` can produce a real tree-sitter tree for languages with a
+//! registered grammar (see `codesage_parser::ParsedCode::tree`), but this
+//! module hasn't been migrated to a true AST walk yet: instead it strips
+//! string/char literals and comments, then recursively walks brace-delimited
+//! blocks applying the cognitive complexity rules:
+//!
+//! - `if`/`else if`/`match`/`for`/`while`/`loop`/`catch` add `1 + nesting`
+//!   and increment `nesting` for their body.
+//! - A plain `else` adds a flat `+1` with no extra nesting.
+//! - Entering a nested `fn`/closure body increments `nesting` for
+//!   everything inside it, without itself adding to the score.
+//! - Each maximal run of the same boolean operator (`&&` or `||`) adds
+//!   `+1`; switching operators starts a new run.
+//! - A call back to the enclosing function's own name adds `+1`.
+
+/// What kind of block a `{` opens, determined by the statement
+/// immediately preceding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opener {
+    If,
+    ElseIf,
+    Else,
+    For,
+    While,
+    Loop,
+    Match,
+    Catch,
+    FnOrClosure,
+    Other,
+}
+
+/// Cognitive complexity of a single function, given its full extracted
+/// text (including the `fn ... {` signature line) and its own name (used
+/// to detect recursive calls).
+pub(crate) fn function_cognitive_complexity(function_body: &str, function_name: &str) -> u32 {
+    let cleaned = strip_strings_and_comments(function_body);
+    let chars: Vec<char> = cleaned.chars().collect();
+
+    // Skip past the function's own signature so it isn't mistaken for a
+    // nested fn/closure definition (which would double-count its nesting).
+    let start = chars
+        .iter()
+        .position(|&c| c == '{')
+        .map(|p| p + 1)
+        .unwrap_or(0);
+
+    let mut complexity = 0u32;
+    walk_block(&chars, start, 0, function_name, &mut complexity);
+    complexity
+}
+
+fn walk_block(
+    chars: &[char],
+    mut i: usize,
+    nesting: u32,
+    fn_name: &str,
+    complexity: &mut u32,
+) -> usize {
+    let mut segment_start = i;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' => {
+                let header = &chars[segment_start..i];
+                score_segment(header, fn_name, complexity);
+
+                let (add, child_nesting) = match classify_opener(header) {
+                    Opener::Else => (1, nesting),
+                    Opener::If
+                    | Opener::ElseIf
+                    | Opener::For
+                    | Opener::While
+                    | Opener::Loop
+                    | Opener::Match
+                    | Opener::Catch => (1 + nesting, nesting + 1),
+                    Opener::FnOrClosure => (0, nesting + 1),
+                    Opener::Other => (0, nesting),
+                };
+                *complexity += add;
+
+                i = walk_block(chars, i + 1, child_nesting, fn_name, complexity);
+                segment_start = i;
+            }
+            '}' => {
+                score_segment(&chars[segment_start..i], fn_name, complexity);
+                return i + 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    score_segment(&chars[segment_start..i], fn_name, complexity);
+    i
+}
+
+/// Score the boolean-operator runs and recursive self-calls in a segment
+/// of code that isn't itself inside a nested brace block.
+fn score_segment(segment: &[char], fn_name: &str, complexity: &mut u32) {
+    let text: String = segment.iter().collect();
+
+    *complexity += count_boolean_operator_runs(&text);
+
+    if !fn_name.is_empty() {
+        *complexity += text.matches(&format!("{}(", fn_name)).count() as u32;
+    }
+}
+
+/// Count `+1` per maximal run of the same boolean operator (`&&`/`||`);
+/// switching operator type starts a new run.
+fn count_boolean_operator_runs(text: &str) -> u32 {
+    let mut runs = 0u32;
+    let mut last_op: Option<&str> = None;
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i + 1 < bytes.len() {
+        let op = match &bytes[i..i + 2] {
+            b"&&" => Some("&&"),
+            b"||" => Some("||"),
+            _ => None,
+        };
+
+        if let Some(op) = op {
+            if last_op != Some(op) {
+                runs += 1;
+                last_op = Some(op);
+            }
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+
+    runs
+}
+
+/// Classify what a `{` opens by looking at the last statement in the text
+/// preceding it (i.e. after the last `;`, if any).
+fn classify_opener(header: &[char]) -> Opener {
+    let header: String = header.iter().collect();
+    let last_statement = header.rsplit(';').next().unwrap_or("").trim();
+
+    if last_statement.starts_with("else if") {
+        Opener::ElseIf
+    } else if last_statement == "else" || last_statement.starts_with("else ") {
+        Opener::Else
+    } else if starts_with_word(last_statement, "if") {
+        Opener::If
+    } else if starts_with_word(last_statement, "for") {
+        Opener::For
+    } else if starts_with_word(last_statement, "while") {
+        Opener::While
+    } else if last_statement == "loop" || starts_with_word(last_statement, "loop") {
+        Opener::Loop
+    } else if starts_with_word(last_statement, "match") {
+        Opener::Match
+    } else if starts_with_word(last_statement, "catch") || last_statement.contains("catch") {
+        Opener::Catch
+    } else if is_fn_or_closure(last_statement) {
+        Opener::FnOrClosure
+    } else {
+        Opener::Other
+    }
+}
+
+fn starts_with_word(text: &str, word: &str) -> bool {
+    text.strip_prefix(word)
+        .is_some_and(|rest| rest.is_empty() || !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+}
+
+/// Whether the statement immediately before a `{` looks like a `fn`
+/// definition or a closure's `|params|` list.
+fn is_fn_or_closure(statement: &str) -> bool {
+    if statement.contains("fn ") || statement.ends_with("fn") {
+        return true;
+    }
+
+    // A closure's parameter list: an odd trailing `|...|`, optionally
+    // preceded by `move`.
+    if let Some(stripped) = statement.strip_prefix("move") {
+        return looks_like_pipe_params(stripped.trim_start());
+    }
+
+    looks_like_pipe_params(statement)
+}
+
+fn looks_like_pipe_params(text: &str) -> bool {
+    text.starts_with('|') && text.matches('|').count() == 2 && text.ends_with('|')
+}
+
+/// Replace the contents of string/char literals and comments with spaces,
+/// preserving line breaks and overall length so callers can still reason
+/// about positions in the original source.
+pub(crate) fn strip_strings_and_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                out.push(' ');
+                out.push(' ');
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    out.push(if chars[i] == '\n' { '\n' } else { ' ' });
+                    i += 1;
+                }
+                if i < chars.len() {
+                    out.push(' ');
+                    out.push(' ');
+                    i += 2;
+                }
+            }
+            '"' => {
+                out.push(' ');
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        out.push(' ');
+                        i += 1;
+                    }
+                    out.push(if chars[i] == '\n' { '\n' } else { ' ' });
+                    i += 1;
+                }
+                if i < chars.len() {
+                    out.push(' ');
+                    i += 1;
+                }
+            }
+            '\'' => {
+                // Distinguish an actual char literal (`'a'`, `'\n'`) from a
+                // lifetime (`'a`), which has no closing quote - treating a
+                // lifetime as an unterminated literal would swallow
+                // everything up to the next quote or newline, including
+                // any brace on the same line.
+                let is_char_literal = (chars.get(i + 1) == Some(&'\\')
+                    && chars.get(i + 3) == Some(&'\''))
+                    || chars.get(i + 2) == Some(&'\'');
+
+                if is_char_literal {
+                    out.push(' ');
+                    i += 1;
+                    while i < chars.len() && chars[i] != '\'' {
+                        out.push(' ');
+                        i += 1;
+                    }
+                    if i < chars.len() {
+                        out.push(' ');
+                        i += 1;
+                    }
+                } else {
+                    out.push('\'');
+                    i += 1;
+                }
+            }
+            other => {
+                out.push(other);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
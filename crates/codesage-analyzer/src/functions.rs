@@ -0,0 +1,79 @@
+//! Per-function extraction shared by the metrics and call-graph analyzers.
+//!
+//! `CodeParser` can produce a real tree-sitter tree for languages with a
+//! registered grammar (see `codesage_parser::ParsedCode::tree`), but this
+//! module hasn't been migrated to walk it yet, so it still splits source
+//! into per-function chunks with a brace-counting heuristic instead of a
+//! proper AST walk. Line numbers are 1-based and refer to the original
+//! source, so callers can build real `Location`s instead of pointing at the
+//! whole file.
+
+/// A function or method extracted from a source file.
+pub(crate) struct ExtractedFunction {
+    pub name: String,
+    pub body: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Split source into per-function chunks, capturing each function's name
+/// and the 1-based line range it spans.
+pub(crate) fn extract_functions(source: &str) -> Vec<ExtractedFunction> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut functions = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let trimmed = lines[i].trim_start();
+        if let Some(name) = function_name(trimmed) {
+            let start = i;
+            let mut depth = 0i32;
+            let mut seen_brace = false;
+            let mut end = i;
+
+            for (offset, line) in lines[i..].iter().enumerate() {
+                depth += line.matches('{').count() as i32;
+                depth -= line.matches('}').count() as i32;
+                if line.contains('{') {
+                    seen_brace = true;
+                }
+                end = i + offset;
+                if seen_brace && depth <= 0 {
+                    break;
+                }
+            }
+
+            functions.push(ExtractedFunction {
+                name,
+                body: lines[start..=end].join("\n"),
+                start_line: start + 1,
+                end_line: end + 1,
+            });
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    functions
+}
+
+/// Extract the function name from a line starting a `fn`/`pub fn`/`async fn` definition.
+pub(crate) fn function_name(line: &str) -> Option<String> {
+    let after_fn = line
+        .strip_prefix("pub async fn ")
+        .or_else(|| line.strip_prefix("pub fn "))
+        .or_else(|| line.strip_prefix("async fn "))
+        .or_else(|| line.strip_prefix("fn "))?;
+
+    let name: String = after_fn
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
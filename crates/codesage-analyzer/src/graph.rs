@@ -0,0 +1,170 @@
+//! Dependency/call graph construction and Graphviz DOT export
+//!
+//! The `Debt` and `Review` commands otherwise only produce flat issue lists;
+//! this builds a function-level call graph (annotated with cyclomatic
+//! complexity) so users can spot hotspots and coupling visually.
+
+use crate::functions::extract_functions;
+use crate::metrics::MetricsAnalyzer;
+use codesage_core::AnalysisContext;
+use std::fmt::Write as _;
+
+/// Whether a graph's edges are directed (call/dependency) or undirected
+/// (co-change clusters).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    /// Directed call/dependency edges, rendered with `->`.
+    Digraph,
+    /// Undirected co-change clusters, rendered with `--`.
+    Graph,
+}
+
+impl GraphKind {
+    fn keyword(&self) -> &'static str {
+        match self {
+            GraphKind::Digraph => "digraph",
+            GraphKind::Graph => "graph",
+        }
+    }
+
+    fn edge_operator(&self) -> &'static str {
+        match self {
+            GraphKind::Digraph => "->",
+            GraphKind::Graph => "--",
+        }
+    }
+}
+
+/// A function or module in the graph, annotated with its cyclomatic
+/// complexity so high-complexity nodes can be colored.
+#[derive(Debug, Clone)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub cyclomatic_complexity: u32,
+}
+
+/// A call or import relationship between two nodes.
+#[derive(Debug, Clone)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// A module/function dependency or call graph, serializable to Graphviz DOT.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    pub kind: GraphKind,
+    pub name: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl DependencyGraph {
+    pub fn new(kind: GraphKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, id: impl Into<String>, label: impl Into<String>, cyclomatic_complexity: u32) {
+        self.nodes.push(GraphNode {
+            id: id.into(),
+            label: label.into(),
+            cyclomatic_complexity,
+        });
+    }
+
+    pub fn add_edge(&mut self, from: impl Into<String>, to: impl Into<String>) {
+        self.edges.push(GraphEdge {
+            from: from.into(),
+            to: to.into(),
+        });
+    }
+
+    /// Serialize this graph to Graphviz DOT text.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "{} \"{}\" {{", self.kind.keyword(), escape(&self.name));
+
+        for node in &self.nodes {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\\ncomplexity: {}\", style=filled, fillcolor=\"{}\"];",
+                escape(&node.id),
+                escape(&node.label),
+                node.cyclomatic_complexity,
+                complexity_color(node.cyclomatic_complexity)
+            );
+        }
+
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "  \"{}\" {} \"{}\";",
+                escape(&edge.from),
+                self.kind.edge_operator(),
+                escape(&edge.to)
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Color a node by its cyclomatic complexity, mirroring the default
+/// thresholds `MetricsAnalyzer` uses to raise `COMPLEXITY001` (above 10,
+/// and at the higher severity above 20).
+fn complexity_color(complexity: u32) -> &'static str {
+    match complexity {
+        0..=5 => "#b7e4c7",
+        6..=10 => "#ffe066",
+        11..=20 => "#ffa94d",
+        _ => "#ff6b6b",
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Build a per-function call graph for a single file: nodes are the
+/// functions it defines (colored by cyclomatic complexity), edges are calls
+/// from one function to another found in the same file.
+pub fn build_call_graph(context: &AnalysisContext) -> DependencyGraph {
+    let functions = extract_functions(&context.source_code);
+    let mut graph = DependencyGraph::new(GraphKind::Digraph, context.file_path.display().to_string());
+
+    for function in &functions {
+        let complexity = MetricsAnalyzer::calculate_cyclomatic_complexity(&function.body);
+        graph.add_node(&function.name, &function.name, complexity);
+    }
+
+    for caller in &functions {
+        for callee in &functions {
+            if caller.name != callee.name && calls(&caller.body, &callee.name) {
+                graph.add_edge(&caller.name, &callee.name);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Whether `body` contains what looks like a call to `name` (`name(`), with
+/// a non-identifier byte (or start of text) immediately before the match -
+/// without that, a plain substring search treats `get(` as a match inside
+/// `target(`/`budget(` too, producing bogus edges in the call graph.
+fn calls(body: &str, name: &str) -> bool {
+    let pattern = format!("{}(", name);
+    body.match_indices(&pattern).any(|(idx, _)| {
+        body[..idx]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+    })
+}
@@ -0,0 +1,90 @@
+//! Halstead complexity metrics, used by `calculate_maintainability_index`
+//! for a real volume term instead of a `lines * ln(cyclomatic)` proxy.
+//!
+//! Every token from `crate::lexer` is classified as an operator (keywords,
+//! punctuation and operator symbols - anything that isn't a name or a
+//! value) or an operand (identifiers and literals). From the distinct and
+//! total counts of each, the standard Halstead formulas give vocabulary,
+//! length, volume, difficulty and effort.
+
+use crate::lexer::{tokenize, RawTokenKind};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct HalsteadMetrics {
+    /// n1: distinct operators.
+    pub distinct_operators: u32,
+    /// n2: distinct operands.
+    pub distinct_operands: u32,
+    /// N1: total operators.
+    pub total_operators: u32,
+    /// N2: total operands.
+    pub total_operands: u32,
+}
+
+impl HalsteadMetrics {
+    /// Program vocabulary `n = n1 + n2`.
+    pub fn vocabulary(&self) -> u32 {
+        self.distinct_operators + self.distinct_operands
+    }
+
+    /// Program length `N = N1 + N2`.
+    pub fn length(&self) -> u32 {
+        self.total_operators + self.total_operands
+    }
+
+    /// Volume `V = N * log2(n)`. Guards the `n <= 1` case (no real
+    /// vocabulary to speak of, e.g. an empty or near-empty file), which
+    /// would otherwise send `log2` to zero or negative and make every
+    /// downstream formula unstable.
+    pub fn volume(&self) -> f32 {
+        let vocabulary = self.vocabulary().max(2) as f32;
+        self.length() as f32 * vocabulary.log2()
+    }
+
+    /// Difficulty `D = (n1 / 2) * (N2 / n2)`.
+    pub fn difficulty(&self) -> f32 {
+        if self.distinct_operands == 0 {
+            return 0.0;
+        }
+        (self.distinct_operators as f32 / 2.0)
+            * (self.total_operands as f32 / self.distinct_operands as f32)
+    }
+
+    /// Effort `E = D * V`.
+    pub fn effort(&self) -> f32 {
+        self.difficulty() * self.volume()
+    }
+}
+
+/// Tally Halstead operator/operand counts for a whole source file.
+pub(crate) fn calculate_halstead_metrics(source: &str) -> HalsteadMetrics {
+    let mut operators = HashSet::new();
+    let mut operands = HashSet::new();
+    let mut total_operators = 0u32;
+    let mut total_operands = 0u32;
+
+    for token in tokenize(source) {
+        match token.kind {
+            RawTokenKind::Keyword | RawTokenKind::Punct => {
+                operators.insert(token.text);
+                total_operators += 1;
+            }
+            RawTokenKind::Ident
+            | RawTokenKind::IntLiteral
+            | RawTokenKind::FloatLiteral
+            | RawTokenKind::StringLiteral
+            | RawTokenKind::CharLiteral => {
+                operands.insert(token.text);
+                total_operands += 1;
+            }
+        }
+    }
+
+    HalsteadMetrics {
+        distinct_operators: operators.len() as u32,
+        distinct_operands: operands.len() as u32,
+        total_operators,
+        total_operands,
+    }
+}
@@ -0,0 +1,186 @@
+//! Minimal shared lexer used by every metric that needs to reason about
+//! tokens rather than raw text or lines (clone detection, Halstead
+//! counts). `CodeParser` can produce a real tree-sitter tree for languages
+//! with a registered grammar (see `codesage_parser::registry`), but nothing
+//! here walks it yet, so this remains a lightweight stand-in: comments and
+//! string/char literal contents are skipped over correctly, but there's no
+//! real grammar behind it.
+
+/// The handful of keywords treated as a single operator-like token rather
+/// than an identifier - shared across every Halstead/clone pass so they
+/// all agree on what counts as "structure" vs. "a name".
+pub(crate) const KEYWORDS: &[&str] = &[
+    "fn", "let", "mut", "if", "else", "for", "while", "loop", "match", "return", "break",
+    "continue", "struct", "enum", "impl", "trait", "pub", "use", "mod", "const", "static",
+    "async", "await", "move", "in", "as", "where", "self", "Self", "dyn", "ref", "type", "crate",
+    "super", "unsafe",
+];
+
+const TWO_CHAR_OPS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "->", "=>", "::", "+=", "-=", "*=", "/=", "%=", "&=",
+    "|=", "^=", "<<", ">>",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RawTokenKind {
+    Ident,
+    Keyword,
+    IntLiteral,
+    FloatLiteral,
+    StringLiteral,
+    CharLiteral,
+    Punct,
+}
+
+pub(crate) struct RawToken {
+    pub kind: RawTokenKind,
+    pub text: String,
+    /// 1-based source line the token starts on.
+    pub line: usize,
+}
+
+/// Tokenize `source`, stripping comments and collapsing each string/char
+/// literal's contents into a single token.
+pub(crate) fn tokenize(source: &str) -> Vec<RawToken> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut line = 1usize;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 2).min(chars.len());
+            }
+            '"' => {
+                let start_line = line;
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    if chars.get(i) == Some(&'\n') {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                tokens.push(RawToken {
+                    kind: RawTokenKind::StringLiteral,
+                    text: chars[start..i].iter().collect(),
+                    line: start_line,
+                });
+            }
+            '\'' => {
+                // Char literal (`'a'`, `'\n'`) vs. lifetime (`'a`), which
+                // has no closing quote and must not swallow the rest of
+                // the line looking for one.
+                let is_char_literal = (chars.get(i + 1) == Some(&'\\')
+                    && chars.get(i + 3) == Some(&'\''))
+                    || chars.get(i + 2) == Some(&'\'');
+                let start_line = line;
+                let start = i;
+                i += 1;
+                if is_char_literal {
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                    i = (i + 1).min(chars.len());
+                    tokens.push(RawToken {
+                        kind: RawTokenKind::CharLiteral,
+                        text: chars[start..i].iter().collect(),
+                        line: start_line,
+                    });
+                } else {
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    tokens.push(RawToken {
+                        kind: RawTokenKind::Punct,
+                        text: "'lifetime".to_string(),
+                        line: start_line,
+                    });
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let start_line = line;
+                let start = i;
+                let mut is_float = false;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    if chars[i] == '.' {
+                        if is_float || chars.get(i + 1) == Some(&'.') {
+                            break;
+                        }
+                        is_float = true;
+                    }
+                    i += 1;
+                }
+                tokens.push(RawToken {
+                    kind: if is_float {
+                        RawTokenKind::FloatLiteral
+                    } else {
+                        RawTokenKind::IntLiteral
+                    },
+                    text: chars[start..i].iter().collect(),
+                    line: start_line,
+                });
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start_line = line;
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(RawToken {
+                    kind: if KEYWORDS.contains(&word.as_str()) {
+                        RawTokenKind::Keyword
+                    } else {
+                        RawTokenKind::Ident
+                    },
+                    text: word,
+                    line: start_line,
+                });
+            }
+            _ => {
+                let start_line = line;
+                let two: String = chars.get(i..i + 2).map(|s| s.iter().collect()).unwrap_or_default();
+                if TWO_CHAR_OPS.contains(&two.as_str()) {
+                    tokens.push(RawToken {
+                        kind: RawTokenKind::Punct,
+                        text: two,
+                        line: start_line,
+                    });
+                    i += 2;
+                } else {
+                    tokens.push(RawToken {
+                        kind: RawTokenKind::Punct,
+                        text: chars[i].to_string(),
+                        line: start_line,
+                    });
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    tokens
+}
@@ -2,8 +2,24 @@
 //!
 //! Static and semantic code analysis
 
+mod abc;
 pub mod analyzer;
+mod ast_complexity;
+mod clone_detection;
+mod cognitive;
+mod functions;
+pub mod graph;
+mod halstead;
+mod lexer;
 pub mod metrics;
+pub mod query;
+pub mod syntax;
 
 pub use analyzer::AnalysisEngine;
-pub use metrics::MetricsAnalyzer;
+pub use graph::{build_call_graph, DependencyGraph, GraphKind};
+pub use metrics::{
+    detect_duplication_across_files, DuplicationSource, FunctionId, MetricsAnalyzer,
+    MetricsThresholds,
+};
+pub use query::{QueryAnalyzer, QueryRule};
+pub use syntax::SyntaxAnalyzer;
@@ -1,21 +1,72 @@
 //! Code metrics analyzer
 
+use crate::abc::function_abc_counts;
+use crate::ast_complexity::{self, AstFunction};
+use crate::clone_detection::{self, ClonableSource};
+use crate::cognitive::function_cognitive_complexity;
+use crate::functions::extract_functions;
+use crate::halstead::calculate_halstead_metrics;
 use codesage_core::{
-    AnalysisContext, Analyzer, CodeMetrics, Issue, IssueCategory, Location, Result,
+    AnalysisContext, Analyzer, CodeMetrics, Issue, IssueCategory, Language, Location, Result,
     Severity,
 };
-use std::collections::HashSet;
+
+/// Thresholds above/below which `MetricsAnalyzer` raises an issue.
+/// Defaults match the values this analyzer has always used; a project's
+/// `codesage.toml` can override them per the `[analyzer]` section.
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsThresholds {
+    pub cyclomatic_complexity: u32,
+    pub cognitive_complexity: u32,
+    pub maintainability_index: f32,
+    pub duplication_percentage: f32,
+    /// ABC magnitude above which `ABC001` is raised as a warning (P2).
+    pub abc_size_warning: f32,
+    /// ABC magnitude above which `ABC001` is raised as an error (P1).
+    pub abc_size_error: f32,
+}
+
+impl Default for MetricsThresholds {
+    fn default() -> Self {
+        Self {
+            cyclomatic_complexity: 10,
+            cognitive_complexity: 15,
+            maintainability_index: 65.0,
+            duplication_percentage: 10.0,
+            abc_size_warning: 17.0,
+            abc_size_error: 35.0,
+        }
+    }
+}
 
 /// Analyzer for code metrics and complexity
-pub struct MetricsAnalyzer;
+pub struct MetricsAnalyzer {
+    thresholds: MetricsThresholds,
+}
 
 impl MetricsAnalyzer {
     pub fn new() -> Self {
-        Self
+        Self {
+            thresholds: MetricsThresholds::default(),
+        }
+    }
+
+    /// Build an analyzer with project-specific thresholds, e.g. loaded from
+    /// `codesage.toml`.
+    pub fn with_thresholds(thresholds: MetricsThresholds) -> Self {
+        Self { thresholds }
+    }
+
+    /// Parse `source` via `codesage_parser::CodeParser`, or `None` if
+    /// `language` has no registered grammar (see
+    /// `codesage_parser::grammar_for`) or parsing otherwise fails.
+    fn parse_tree(source: &str, language: Language) -> Option<codesage_parser::ParsedCode> {
+        let mut parser = codesage_parser::CodeParser::new();
+        parser.parse_source(source, language).ok()
     }
 
     /// Calculate cyclomatic complexity (simplified version)
-    fn calculate_cyclomatic_complexity(source: &str) -> u32 {
+    pub(crate) fn calculate_cyclomatic_complexity(source: &str) -> u32 {
         let mut complexity = 1u32;
 
         // Count decision points
@@ -43,65 +94,47 @@ impl MetricsAnalyzer {
         complexity
     }
 
-    /// Calculate cognitive complexity (simplified)
+    /// Cognitive complexity of the whole file, taken as its most complex
+    /// function (the one that would need fixing first). See
+    /// `cognitive::function_cognitive_complexity` for the per-function
+    /// SonarSource algorithm; `analyze` below reports each offending
+    /// function individually rather than relying on this rollup.
     fn calculate_cognitive_complexity(source: &str) -> u32 {
-        let mut complexity = 0u32;
-        let mut nesting_level = 0u32;
-
-        for line in source.lines() {
-            let line = line.trim();
-
-            // Track nesting
-            if line.contains('{') {
-                nesting_level += 1;
-            }
-            if line.contains('}') {
-                nesting_level = nesting_level.saturating_sub(1);
-            }
+        let functions = extract_functions(source);
 
-            // Add complexity based on control structures and nesting
-            if line.contains("if ") || line.contains("else if") {
-                complexity += nesting_level + 1;
-            }
-            if line.contains("while ") || line.contains("for ") {
-                complexity += nesting_level + 1;
-            }
+        if functions.is_empty() {
+            return function_cognitive_complexity(source, "");
         }
 
-        complexity
+        functions
+            .iter()
+            .map(|f| function_cognitive_complexity(&f.body, &f.name))
+            .max()
+            .unwrap_or(0)
     }
 
-    /// Calculate maintainability index (simplified)
-    fn calculate_maintainability_index(source: &str, cyclomatic: u32) -> f32 {
-        let lines = source.lines().count() as f32;
-        let volume = lines * (cyclomatic as f32).ln();
+    /// Calculate maintainability index from the standard formula
+    /// `171 - 5.2*ln(V) - 0.23*G - 16.2*ln(LOC)`, fed by a real Halstead
+    /// volume `V` rather than an approximation.
+    fn calculate_maintainability_index(lines_of_code: usize, cyclomatic: u32, halstead_volume: f32) -> f32 {
+        // Guard V<=0 (an essentially empty file) and LOC<=1, both of which
+        // would otherwise send `ln` to zero or negative and produce NaN.
+        let volume = halstead_volume.max(1.0);
+        let lines = (lines_of_code as f32).max(1.0);
 
-        // Simplified MI = 171 - 5.2 * ln(V) - 0.23 * G - 16.2 * ln(LOC)
         let mi = 171.0 - 5.2 * volume.ln() - 0.23 * (cyclomatic as f32) - 16.2 * lines.ln();
         mi.clamp(0.0, 100.0)
     }
 
-    /// Detect code duplication (simplified)
+    /// Detect code duplication via Type-2 clone detection: identifier
+    /// renames and changed literals still count as a duplicate, unlike
+    /// exact-line comparison. See `clone_detection` for the algorithm.
     fn calculate_duplication_percentage(source: &str) -> f32 {
-        let lines: Vec<&str> = source
-            .lines()
-            .filter(|l| !l.trim().is_empty() && !l.trim().starts_with("//"))
-            .collect();
-
-        if lines.is_empty() {
-            return 0.0;
-        }
-
-        let mut seen = HashSet::new();
-        let mut duplicates = 0;
-
-        for line in &lines {
-            if !seen.insert(line.trim()) {
-                duplicates += 1;
-            }
-        }
-
-        (duplicates as f32 / lines.len() as f32) * 100.0
+        let sources = [ClonableSource {
+            file_path: std::path::PathBuf::new(),
+            source_code: source.to_string(),
+        }];
+        clone_detection::detect_clones(&sources).duplication_percentage
     }
 
     /// Calculate technical debt in minutes
@@ -139,10 +172,13 @@ impl MetricsAnalyzer {
         let lines_of_code = source.lines().count();
         let cyclomatic = Self::calculate_cyclomatic_complexity(source);
         let cognitive = Self::calculate_cognitive_complexity(source);
-        let maintainability = Self::calculate_maintainability_index(source, cyclomatic);
+        let halstead = calculate_halstead_metrics(source);
+        let maintainability =
+            Self::calculate_maintainability_index(lines_of_code, cyclomatic, halstead.volume());
         let duplication = Self::calculate_duplication_percentage(source);
         let technical_debt =
             Self::calculate_technical_debt(cyclomatic, cognitive, duplication, maintainability);
+        let abc_size = Self::calculate_abc_size(source);
 
         CodeMetrics {
             lines_of_code,
@@ -152,7 +188,225 @@ impl MetricsAnalyzer {
             test_coverage: None,
             duplication_percentage: duplication,
             technical_debt_minutes: technical_debt,
+            abc_size,
+            halstead_volume: halstead.volume(),
+            halstead_difficulty: halstead.difficulty(),
+            halstead_effort: halstead.effort(),
+        }
+    }
+
+    /// ABC magnitude of the whole file, taken as its most ABC-heavy
+    /// function. `analyze` below reports each offending function
+    /// individually via `ABC001` rather than relying on this rollup.
+    fn calculate_abc_size(source: &str) -> f32 {
+        let functions = extract_functions(source);
+
+        if functions.is_empty() {
+            return function_abc_counts(source).magnitude();
         }
+
+        functions
+            .iter()
+            .map(|f| function_abc_counts(&f.body).magnitude())
+            .fold(0.0, f32::max)
+    }
+
+    /// Metrics for each function/method definition found in `source`,
+    /// keyed by its true span rather than the whole file's. `analyze`
+    /// scopes COMPLEXITY001/002 and MAINTAINABILITY001 to these so a
+    /// warning in a 2000-line file points at the offending method instead
+    /// of the entire file; `calculate_metrics` above still provides the
+    /// file-level rollup used for summaries and `DUPLICATION001`.
+    pub fn calculate_function_metrics(source: &str) -> Vec<(FunctionId, CodeMetrics)> {
+        extract_functions(source)
+            .iter()
+            .map(|f| {
+                let id = FunctionId {
+                    name: f.name.clone(),
+                    start_line: f.start_line,
+                    end_line: f.end_line,
+                };
+                (id, Self::calculate_metrics_for_function(&f.body, &f.name))
+            })
+            .collect()
+    }
+
+    fn calculate_metrics_for_function(body: &str, name: &str) -> CodeMetrics {
+        let lines_of_code = body.lines().count();
+        let cyclomatic = Self::calculate_cyclomatic_complexity(body);
+        let cognitive = function_cognitive_complexity(body, name);
+        let halstead = calculate_halstead_metrics(body);
+        let maintainability =
+            Self::calculate_maintainability_index(lines_of_code, cyclomatic, halstead.volume());
+        let abc_size = function_abc_counts(body).magnitude();
+
+        CodeMetrics {
+            lines_of_code,
+            cyclomatic_complexity: cyclomatic,
+            cognitive_complexity: cognitive,
+            maintainability_index: maintainability,
+            test_coverage: None,
+            // Duplication and technical debt are file/project-scoped
+            // concepts; see `calculate_metrics`'s aggregate for those.
+            duplication_percentage: 0.0,
+            technical_debt_minutes: 0,
+            abc_size,
+            halstead_volume: halstead.volume(),
+            halstead_difficulty: halstead.difficulty(),
+            halstead_effort: halstead.effort(),
+        }
+    }
+}
+
+/// A single function/method definition's identity within its file: its
+/// name and the line span `analyze` should point issues at.
+#[derive(Debug, Clone)]
+pub struct FunctionId {
+    pub name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Build a `COMPLEXITY001` issue for a function (or, if `function_name` is
+/// `None`, for a file with no detected functions) whose cyclomatic
+/// complexity exceeds the configured threshold.
+fn complexity001_issue(
+    context: &AnalysisContext,
+    start_line: usize,
+    end_line: usize,
+    function_name: Option<&str>,
+    cyclomatic_complexity: u32,
+    threshold: u32,
+) -> Issue {
+    let message = match function_name {
+        Some(name) => format!("High cyclomatic complexity in `{}`: {}", name, cyclomatic_complexity),
+        None => format!("High cyclomatic complexity: {}", cyclomatic_complexity),
+    };
+
+    Issue {
+        id: "COMPLEXITY001".to_string(),
+        severity: if cyclomatic_complexity > threshold * 2 {
+            Severity::P1
+        } else {
+            Severity::P2
+        },
+        category: IssueCategory::Maintainability,
+        location: Location {
+            file_path: context.file_path.clone(),
+            start_line,
+            start_column: 1,
+            end_line,
+            end_column: 1,
+        },
+        message,
+        explanation: "This code has high cyclomatic complexity, making it harder to understand and test. Consider breaking it into smaller functions.".to_string(),
+        fix_suggestion: None,
+        confidence: 0.9,
+        related_locations: Vec::new(),
+    }
+}
+
+/// Build a `MAINTAINABILITY001` issue for a function (or, if
+/// `function_name` is `None`, for a file with no detected functions)
+/// whose maintainability index falls below the configured threshold.
+fn maintainability001_issue(
+    context: &AnalysisContext,
+    start_line: usize,
+    end_line: usize,
+    function_name: Option<&str>,
+    maintainability_index: f32,
+) -> Issue {
+    let message = match function_name {
+        Some(name) => format!("Low maintainability index in `{}`: {:.1}", name, maintainability_index),
+        None => format!("Low maintainability index: {:.1}", maintainability_index),
+    };
+
+    Issue {
+        id: "MAINTAINABILITY001".to_string(),
+        severity: Severity::P2,
+        category: IssueCategory::Maintainability,
+        location: Location {
+            file_path: context.file_path.clone(),
+            start_line,
+            start_column: 1,
+            end_line,
+            end_column: 1,
+        },
+        message,
+        explanation: "This code has a low maintainability index. Consider refactoring to improve code quality.".to_string(),
+        fix_suggestion: None,
+        confidence: 0.8,
+        related_locations: Vec::new(),
+    }
+}
+
+/// Build a `COMPLEXITY002` issue for a function (or, if `function_name` is
+/// `None`, for a file with no detected functions) whose cognitive
+/// complexity exceeds the configured threshold.
+fn complexity002_issue(
+    context: &AnalysisContext,
+    start_line: usize,
+    end_line: usize,
+    function_name: Option<&str>,
+    cognitive_complexity: u32,
+) -> Issue {
+    let message = match function_name {
+        Some(name) => format!("High cognitive complexity in `{}`: {}", name, cognitive_complexity),
+        None => format!("High cognitive complexity: {}", cognitive_complexity),
+    };
+
+    Issue {
+        id: "COMPLEXITY002".to_string(),
+        severity: Severity::P2,
+        category: IssueCategory::Maintainability,
+        location: Location {
+            file_path: context.file_path.clone(),
+            start_line,
+            start_column: 1,
+            end_line,
+            end_column: 1,
+        },
+        message,
+        explanation: "This code has high cognitive complexity with deep nesting. Consider refactoring to reduce nesting levels.".to_string(),
+        fix_suggestion: None,
+        confidence: 0.85,
+        related_locations: Vec::new(),
+    }
+}
+
+/// Build an `ABC001` issue for a function whose ABC magnitude exceeds the
+/// configured warning (or error) threshold.
+#[allow(clippy::too_many_arguments)]
+fn abc001_issue(
+    context: &AnalysisContext,
+    start_line: usize,
+    end_line: usize,
+    function_name: &str,
+    counts: crate::abc::AbcCounts,
+    magnitude: f32,
+    is_error: bool,
+) -> Issue {
+    Issue {
+        id: "ABC001".to_string(),
+        severity: if is_error { Severity::P1 } else { Severity::P2 },
+        category: IssueCategory::Maintainability,
+        location: Location {
+            file_path: context.file_path.clone(),
+            start_line,
+            start_column: 1,
+            end_line,
+            end_column: 1,
+        },
+        message: format!("High ABC size in `{}`: {:.1}", function_name, magnitude),
+        explanation: format!(
+            "ABC magnitude {:.1} = sqrt(A={}^2 + B={}^2 + C={}^2). High assignment, branch, \
+             or condition counts make this function hard to follow even when its cyclomatic \
+             complexity looks reasonable. Consider splitting it up.",
+            magnitude, counts.assignments, counts.branches, counts.conditions
+        ),
+        fix_suggestion: None,
+        confidence: 0.75,
+        related_locations: Vec::new(),
     }
 }
 
@@ -171,78 +425,147 @@ impl Analyzer for MetricsAnalyzer {
         let metrics = self.calculate_metrics(&context.source_code);
         let mut issues = Vec::new();
 
-        // Generate issues based on metrics
-        if metrics.cyclomatic_complexity > 10 {
-            issues.push(Issue {
-                id: "COMPLEXITY001".to_string(),
-                severity: if metrics.cyclomatic_complexity > 20 {
-                    Severity::P1
-                } else {
-                    Severity::P2
-                },
-                category: IssueCategory::Maintainability,
-                location: Location {
-                    file_path: context.file_path.clone(),
-                    start_line: 1,
-                    start_column: 1,
-                    end_line: metrics.lines_of_code,
-                    end_column: 1,
-                },
-                message: format!(
-                    "High cyclomatic complexity: {}",
-                    metrics.cyclomatic_complexity
-                ),
-                explanation: "This code has high cyclomatic complexity, making it harder to understand and test. Consider breaking it into smaller functions.".to_string(),
-                fix_suggestion: None,
-                confidence: 0.9,
-            });
-        }
+        // When a grammar is registered for this file's language, prefer
+        // walking its real syntax tree for cyclomatic/cognitive complexity:
+        // a string literal or comment can never be mistaken for a decision
+        // point that way, unlike the text heuristics below. Falls back to
+        // those heuristics for an unregistered language or a parse failure.
+        let ast_tree = Self::parse_tree(&context.source_code, context.language);
+        let ast_functions: Vec<AstFunction> = ast_tree
+            .as_ref()
+            .and_then(|parsed| parsed.root_node())
+            .map(|root| ast_complexity::functions_in_tree(root, context.source_code.as_bytes()))
+            .unwrap_or_default();
+
+        // Cyclomatic complexity, cognitive complexity and maintainability
+        // index are all reported per function, so each issue points at the
+        // offending function's own lines rather than the whole file.
+        let functions = extract_functions(&context.source_code);
+        if functions.is_empty() {
+            let root = ast_tree.as_ref().and_then(|parsed| parsed.root_node());
+            let cyclomatic = root
+                .map(ast_complexity::cyclomatic_complexity)
+                .unwrap_or(metrics.cyclomatic_complexity);
+            if cyclomatic > self.thresholds.cyclomatic_complexity {
+                issues.push(complexity001_issue(
+                    context,
+                    1,
+                    metrics.lines_of_code,
+                    None,
+                    cyclomatic,
+                    self.thresholds.cyclomatic_complexity,
+                ));
+            }
 
-        if metrics.cognitive_complexity > 15 {
-            issues.push(Issue {
-                id: "COMPLEXITY002".to_string(),
-                severity: Severity::P2,
-                category: IssueCategory::Maintainability,
-                location: Location {
-                    file_path: context.file_path.clone(),
-                    start_line: 1,
-                    start_column: 1,
-                    end_line: metrics.lines_of_code,
-                    end_column: 1,
-                },
-                message: format!(
-                    "High cognitive complexity: {}",
-                    metrics.cognitive_complexity
-                ),
-                explanation: "This code has high cognitive complexity with deep nesting. Consider refactoring to reduce nesting levels.".to_string(),
-                fix_suggestion: None,
-                confidence: 0.85,
-            });
+            let cognitive = root
+                .map(|root| ast_complexity::cognitive_complexity(root, "", context.source_code.as_bytes()))
+                .unwrap_or_else(|| function_cognitive_complexity(&context.source_code, ""));
+            if cognitive > self.thresholds.cognitive_complexity {
+                issues.push(complexity002_issue(
+                    context,
+                    1,
+                    metrics.lines_of_code,
+                    None,
+                    cognitive,
+                ));
+            }
+
+            if metrics.maintainability_index < self.thresholds.maintainability_index {
+                issues.push(maintainability001_issue(
+                    context,
+                    1,
+                    metrics.lines_of_code,
+                    None,
+                    metrics.maintainability_index,
+                ));
+            }
+        } else {
+            for function in &functions {
+                let ast_function = ast_functions
+                    .iter()
+                    .find(|f| f.start_line == function.start_line);
+
+                let cyclomatic = ast_function
+                    .map(|f| ast_complexity::cyclomatic_complexity(f.node))
+                    .unwrap_or_else(|| Self::calculate_cyclomatic_complexity(&function.body));
+                if cyclomatic > self.thresholds.cyclomatic_complexity {
+                    issues.push(complexity001_issue(
+                        context,
+                        function.start_line,
+                        function.end_line,
+                        Some(function.name.as_str()),
+                        cyclomatic,
+                        self.thresholds.cyclomatic_complexity,
+                    ));
+                }
+
+                let cognitive = ast_function
+                    .map(|f| {
+                        ast_complexity::cognitive_complexity(
+                            f.node,
+                            &function.name,
+                            context.source_code.as_bytes(),
+                        )
+                    })
+                    .unwrap_or_else(|| function_cognitive_complexity(&function.body, &function.name));
+                if cognitive > self.thresholds.cognitive_complexity {
+                    issues.push(complexity002_issue(
+                        context,
+                        function.start_line,
+                        function.end_line,
+                        Some(function.name.as_str()),
+                        cognitive,
+                    ));
+                }
+
+                let volume = calculate_halstead_metrics(&function.body).volume();
+                let maintainability = Self::calculate_maintainability_index(
+                    function.body.lines().count(),
+                    cyclomatic,
+                    volume,
+                );
+                if maintainability < self.thresholds.maintainability_index {
+                    issues.push(maintainability001_issue(
+                        context,
+                        function.start_line,
+                        function.end_line,
+                        Some(function.name.as_str()),
+                        maintainability,
+                    ));
+                }
+            }
         }
 
-        if metrics.maintainability_index < 65.0 {
-            issues.push(Issue {
-                id: "MAINTAINABILITY001".to_string(),
-                severity: Severity::P2,
-                category: IssueCategory::Maintainability,
-                location: Location {
-                    file_path: context.file_path.clone(),
-                    start_line: 1,
-                    start_column: 1,
-                    end_line: metrics.lines_of_code,
-                    end_column: 1,
-                },
-                message: format!(
-                    "Low maintainability index: {:.1}",
-                    metrics.maintainability_index
-                ),
-                explanation: "This code has a low maintainability index. Consider refactoring to improve code quality.".to_string(),
-                fix_suggestion: None,
-                confidence: 0.8,
-            });
+        // ABC size is also reported per function, same as cognitive
+        // complexity, so ABC001 points at the offending function.
+        for function in &functions {
+            let counts = function_abc_counts(&function.body);
+            let magnitude = counts.magnitude();
+
+            if magnitude > self.thresholds.abc_size_warning {
+                issues.push(abc001_issue(
+                    context,
+                    function.start_line,
+                    function.end_line,
+                    &function.name,
+                    counts,
+                    magnitude,
+                    magnitude > self.thresholds.abc_size_error,
+                ));
+            }
         }
 
-        if metrics.duplication_percentage > 10.0 {
+        if metrics.duplication_percentage > self.thresholds.duplication_percentage {
+            let sources = [ClonableSource {
+                file_path: context.file_path.clone(),
+                source_code: context.source_code.clone(),
+            }];
+            let related_locations = clone_detection::detect_clones(&sources)
+                .clone_groups
+                .into_iter()
+                .flat_map(|group| clone_detection::instances_to_locations(&group))
+                .collect();
+
             issues.push(Issue {
                 id: "DUPLICATION001".to_string(),
                 severity: Severity::P3,
@@ -261,9 +584,72 @@ impl Analyzer for MetricsAnalyzer {
                 explanation: "Duplicate code has been detected. Consider extracting common code into reusable functions.".to_string(),
                 fix_suggestion: None,
                 confidence: 0.7,
+                related_locations,
             });
         }
 
         Ok(issues)
     }
 }
+
+/// One file's content, as analyzed by `detect_duplication_across_files`.
+pub struct DuplicationSource {
+    pub file_path: std::path::PathBuf,
+    pub source_code: String,
+}
+
+/// Find Type-2 clones that cross a file boundary and report one
+/// `DUPLICATION001` issue per clone, with every copy's location in
+/// `Issue::related_locations`. `MetricsAnalyzer::analyze` only ever sees a
+/// single file, so this is the entry point a multi-file review (e.g. a
+/// recursive project scan) should call separately to catch copy-paste that
+/// spans files.
+pub fn detect_duplication_across_files(sources: &[DuplicationSource]) -> Vec<Issue> {
+    let clonable: Vec<ClonableSource> = sources
+        .iter()
+        .map(|s| ClonableSource {
+            file_path: s.file_path.clone(),
+            source_code: s.source_code.clone(),
+        })
+        .collect();
+
+    clone_detection::detect_clones(&clonable)
+        .clone_groups
+        .into_iter()
+        .filter(|group| {
+            group
+                .windows(2)
+                .any(|pair| pair[0].file_path != pair[1].file_path)
+        })
+        .map(duplication_across_files_issue)
+        .collect()
+}
+
+fn duplication_across_files_issue(instances: Vec<crate::clone_detection::CloneInstance>) -> Issue {
+    let locations = clone_detection::instances_to_locations(&instances);
+    let (location, related_locations) = match locations.split_first() {
+        Some((first, rest)) => (first.clone(), rest.to_vec()),
+        None => unreachable!("a clone group always has at least 2 instances"),
+    };
+
+    let other_copies: Vec<String> = instances[1..]
+        .iter()
+        .map(|i| format!("{}:{}", i.file_path.display(), i.start_line))
+        .collect();
+
+    Issue {
+        id: "DUPLICATION001".to_string(),
+        severity: Severity::P3,
+        category: IssueCategory::Maintainability,
+        location,
+        message: format!(
+            "Code duplicated in {} other location(s): {}",
+            other_copies.len(),
+            other_copies.join(", ")
+        ),
+        explanation: "This block matches code elsewhere with only identifiers or literals changed. Consider extracting a shared function.".to_string(),
+        fix_suggestion: None,
+        confidence: 0.7,
+        related_locations,
+    }
+}
@@ -0,0 +1,311 @@
+//! Tree-sitter query–based custom rule engine.
+//!
+//! `QueryAnalyzer` loads user-authored tree-sitter S-expression queries
+//! (`.scm` files, one `QueryRule` each) and matches them against the real
+//! syntax tree `codesage_parser::CodeParser` produces for a registered
+//! grammar, emitting an `Issue` per match instead of the string-`contains`
+//! heuristics the rest of this crate still falls back on (see
+//! `crate::lexer`). This lets a project add its own structural lints
+//! without recompiling CodeSage, the same way `codesage_refactor::ssr`
+//! lets it add rewrite rules.
+
+use codesage_core::{
+    AnalysisContext, Analyzer, CodeSageError, Issue, IssueCategory, Language, Location, Result,
+    Severity,
+};
+use codesage_parser::CodeParser;
+use std::collections::HashMap;
+use std::path::Path;
+use tree_sitter::{Query, QueryCursor, QueryMatch};
+
+/// One user-authored structural lint: a tree-sitter query paired with how
+/// to report a match against it. A rule file (see `from_rule_text`) is a
+/// `.scm` query body preceded by a `;; key: value` metadata header, e.g.:
+///
+/// ```text
+/// ;; id: NO_UNWRAP
+/// ;; severity: P2
+/// ;; category: Maintainability
+/// ;; capture: call
+/// ;; message: avoid `.unwrap()`; handle the error instead
+///
+/// (call_expression
+///   function: (field_expression field: (field_identifier) @method)
+///   (#eq? @method "unwrap")) @call
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryRule {
+    /// Issue id this rule raises, e.g. `"NO_UNWRAP"`.
+    pub id: String,
+    pub severity: Severity,
+    pub category: IssueCategory,
+    /// Message template; `{capture}` is replaced with that capture's
+    /// matched source text, so `"unused import {name}"` can reference a
+    /// `@name` capture from the query.
+    pub message_template: String,
+    /// Capture (without its leading `@`) whose start/end position anchors
+    /// the reported `Location`.
+    pub anchor_capture: String,
+    query_source: String,
+}
+
+impl QueryRule {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: impl Into<String>,
+        severity: Severity,
+        category: IssueCategory,
+        message_template: impl Into<String>,
+        anchor_capture: impl Into<String>,
+        query_source: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            severity,
+            category,
+            message_template: message_template.into(),
+            anchor_capture: anchor_capture.into(),
+            query_source: query_source.into(),
+        }
+    }
+
+    /// Parse a rule file's `;; key: value` metadata header followed by its
+    /// query body. Recognized keys: `id`, `severity` (`P0`-`P3`),
+    /// `category` (an `IssueCategory` variant name), `capture`, `message`.
+    /// The first line that isn't a `;; key: value` header ends the header
+    /// and starts the query body (so the query itself can still use `;;`
+    /// comments of its own).
+    pub fn from_rule_text(text: &str) -> Result<Self> {
+        let mut id = None;
+        let mut severity = None;
+        let mut category = None;
+        let mut message_template = None;
+        let mut anchor_capture = None;
+        let mut body_start = 0;
+
+        for (offset, line) in line_offsets(text) {
+            let trimmed = line.trim();
+            // Blank lines are allowed around and between header lines (e.g.
+            // the one separating the header block from the query body)
+            // without ending the header.
+            if trimmed.is_empty() {
+                continue;
+            }
+            let Some(header) = trimmed.strip_prefix(";;") else {
+                break;
+            };
+            let Some((key, value)) = header.split_once(':') else {
+                break;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "id" => id = Some(value),
+                "severity" => severity = Some(parse_severity(&value)?),
+                "category" => category = Some(parse_category(&value)?),
+                "message" => message_template = Some(value),
+                "capture" => anchor_capture = Some(value),
+                other => {
+                    return Err(CodeSageError::ConfigError(format!(
+                        "unknown query rule header key `{}`",
+                        other
+                    )))
+                }
+            }
+            body_start = offset + line.len();
+        }
+
+        Ok(Self {
+            id: id.ok_or_else(|| missing_header("id"))?,
+            severity: severity.ok_or_else(|| missing_header("severity"))?,
+            category: category.ok_or_else(|| missing_header("category"))?,
+            message_template: message_template.ok_or_else(|| missing_header("message"))?,
+            anchor_capture: anchor_capture.ok_or_else(|| missing_header("capture"))?,
+            query_source: text[body_start..].trim().to_string(),
+        })
+    }
+
+    /// Load a rule from a `.scm` file on disk (see `from_rule_text`).
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::from_rule_text(&text)
+    }
+}
+
+fn missing_header(key: &str) -> CodeSageError {
+    CodeSageError::ConfigError(format!("query rule is missing its `;; {}:` header", key))
+}
+
+fn parse_severity(value: &str) -> Result<Severity> {
+    match value {
+        "P0" => Ok(Severity::P0),
+        "P1" => Ok(Severity::P1),
+        "P2" => Ok(Severity::P2),
+        "P3" => Ok(Severity::P3),
+        other => Err(CodeSageError::ConfigError(format!(
+            "unknown severity `{}`, expected one of P0..P3",
+            other
+        ))),
+    }
+}
+
+fn parse_category(value: &str) -> Result<IssueCategory> {
+    match value {
+        "Bug" => Ok(IssueCategory::Bug),
+        "Security" => Ok(IssueCategory::Security),
+        "Performance" => Ok(IssueCategory::Performance),
+        "Maintainability" => Ok(IssueCategory::Maintainability),
+        "Style" => Ok(IssueCategory::Style),
+        "Documentation" => Ok(IssueCategory::Documentation),
+        "TestCoverage" => Ok(IssueCategory::TestCoverage),
+        other => Err(CodeSageError::ConfigError(format!(
+            "unknown issue category `{}`",
+            other
+        ))),
+    }
+}
+
+/// `(byte_offset_of_line_start, line_including_its_newline)` pairs, so a
+/// header's end offset can be sliced straight out of the original text.
+fn line_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    text.split_inclusive('\n').map(move |line| {
+        let start = offset;
+        offset += line.len();
+        (start, line)
+    })
+}
+
+struct CompiledRule {
+    rule: QueryRule,
+    query: Query,
+}
+
+/// Analyzer that matches a fixed set of `QueryRule`s, all written against
+/// the same `language`'s grammar, against each file's real syntax tree.
+pub struct QueryAnalyzer {
+    language: Language,
+    rules: Vec<CompiledRule>,
+}
+
+impl QueryAnalyzer {
+    /// Compile `rules` against `language`'s tree-sitter grammar. Fails if
+    /// the grammar isn't registered (see `codesage_parser::grammar_for`)
+    /// or if any rule's query doesn't parse.
+    pub fn new(language: Language, rules: Vec<QueryRule>) -> Result<Self> {
+        let grammar = codesage_parser::grammar_for(language).ok_or_else(|| {
+            CodeSageError::UnsupportedLanguage(format!("{:?}", language))
+        })?;
+
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let query = Query::new(&grammar, &rule.query_source).map_err(|e| {
+                    CodeSageError::AnalysisError(format!(
+                        "query rule `{}` failed to compile: {}",
+                        rule.id, e
+                    ))
+                })?;
+                Ok(CompiledRule { rule, query })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { language, rules })
+    }
+
+    /// Load every `.scm` file in `dir` as a `QueryRule` and compile them
+    /// against `language`'s grammar.
+    pub fn load_dir(language: Language, dir: &Path) -> Result<Self> {
+        let mut rules = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("scm") {
+                rules.push(QueryRule::load_file(&path)?);
+            }
+        }
+        Self::new(language, rules)
+    }
+
+    fn issue_for_match(
+        &self,
+        context: &AnalysisContext,
+        compiled: &CompiledRule,
+        query_match: &QueryMatch,
+        source: &[u8],
+    ) -> Option<Issue> {
+        let mut captures = HashMap::new();
+        let mut anchor = None;
+
+        for capture in query_match.captures {
+            let name = compiled.query.capture_names()[capture.index as usize];
+            let text = capture.node.utf8_text(source).unwrap_or_default();
+            if name == compiled.rule.anchor_capture {
+                anchor = Some(capture.node);
+            }
+            captures.insert(name, text);
+        }
+
+        let anchor = anchor?;
+        let start = anchor.start_position();
+        let end = anchor.end_position();
+
+        Some(Issue {
+            id: compiled.rule.id.clone(),
+            severity: compiled.rule.severity,
+            category: compiled.rule.category.clone(),
+            location: Location {
+                file_path: context.file_path.clone(),
+                start_line: start.row + 1,
+                start_column: start.column + 1,
+                end_line: end.row + 1,
+                end_column: end.column + 1,
+            },
+            message: render_message(&compiled.rule.message_template, &captures),
+            explanation: format!("Matched custom query rule `{}`.", compiled.rule.id),
+            fix_suggestion: None,
+            confidence: 0.8,
+            related_locations: Vec::new(),
+        })
+    }
+}
+
+/// Replace each `{capture}` placeholder in `template` with that capture's
+/// matched source text; a placeholder with no matching capture is left
+/// as-is rather than silently dropped, so a typo'd name is easy to spot.
+fn render_message(template: &str, captures: &HashMap<&str, &str>) -> String {
+    let mut message = template.to_string();
+    for (name, text) in captures {
+        message = message.replace(&format!("{{{}}}", name), text);
+    }
+    message
+}
+
+impl Analyzer for QueryAnalyzer {
+    fn name(&self) -> &str {
+        "query"
+    }
+
+    fn analyze(&self, context: &AnalysisContext) -> Result<Vec<Issue>> {
+        if context.language != self.language {
+            return Ok(Vec::new());
+        }
+
+        let mut parser = CodeParser::new();
+        let parsed = parser.parse_source(&context.source_code, context.language)?;
+        let Some(root) = parsed.root_node() else {
+            return Ok(Vec::new());
+        };
+
+        let source = context.source_code.as_bytes();
+        let mut issues = Vec::new();
+        for compiled in &self.rules {
+            let mut cursor = QueryCursor::new();
+            for query_match in cursor.matches(&compiled.query, root, source) {
+                if let Some(issue) = self.issue_for_match(context, compiled, &query_match, source) {
+                    issues.push(issue);
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+}
@@ -0,0 +1,62 @@
+//! Surfaces tree-sitter's parse-error recovery as `Issue`s.
+//!
+//! `parse_source` no longer aborts a whole file on the first syntax error -
+//! it returns a `ParsedCode` recovered around the problem plus a
+//! `ParseDiagnostic` per `ERROR`/`MISSING` node (see
+//! `codesage_parser::ParsedCode::diagnostics`). `SyntaxAnalyzer` is the
+//! `Analyzer` that reports those diagnostics, so a file under active
+//! editing still gets reviewed instead of being skipped outright.
+
+use codesage_core::{AnalysisContext, Analyzer, Issue, IssueCategory, Location, Result, Severity};
+use codesage_parser::CodeParser;
+
+/// Reports each parse-recovery diagnostic tree-sitter left in a file's
+/// syntax tree as a `Bug` issue.
+pub struct SyntaxAnalyzer;
+
+impl SyntaxAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for SyntaxAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Analyzer for SyntaxAnalyzer {
+    fn name(&self) -> &str {
+        "syntax"
+    }
+
+    fn analyze(&self, context: &AnalysisContext) -> Result<Vec<Issue>> {
+        let mut parser = CodeParser::new();
+        let parsed = parser.parse_source(&context.source_code, context.language)?;
+
+        Ok(parsed
+            .diagnostics()
+            .into_iter()
+            .map(|diagnostic| Issue {
+                id: "SYNTAX001".to_string(),
+                severity: Severity::P1,
+                category: IssueCategory::Bug,
+                location: Location {
+                    file_path: context.file_path.clone(),
+                    start_line: diagnostic.start_line,
+                    start_column: diagnostic.start_column,
+                    end_line: diagnostic.end_line,
+                    end_column: diagnostic.end_column,
+                },
+                message: diagnostic.message,
+                explanation: "The parser could not fully understand this code; analysis \
+                    continued on the recovered syntax tree."
+                    .to_string(),
+                fix_suggestion: None,
+                confidence: 0.6,
+                related_locations: Vec::new(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,167 @@
+//! Project configuration loaded from `codesage.toml`
+//!
+//! Settings are resolved with CLI flags > environment variables > config
+//! file > built-in defaults. Each layer only needs to know about the
+//! fields it actually overrides; `Config` itself always holds a complete,
+//! defaulted picture so callers never have to special-case "unset".
+
+use codesage_analyzer::MetricsThresholds;
+use codesage_core::{CodeSageError, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE_NAME: &str = "codesage.toml";
+
+/// Project configuration, merged on top of built-in defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub ai: AiConfigSection,
+    pub analyzer: AnalyzerConfigSection,
+    /// Fix policy keyed by `IssueCategory` name (e.g. "maintainability").
+    pub fix: HashMap<String, FixPolicy>,
+    pub ignore: IgnoreConfigSection,
+}
+
+/// AI-related overrides. Every field is optional so a project can pin only
+/// the model and inherit everything else from `AIConfig::default()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AiConfigSection {
+    pub model: Option<String>,
+    pub api_base_url: Option<String>,
+    pub timeout_seconds: Option<u64>,
+    pub context_window_tokens: Option<usize>,
+    /// One of "anthropic", "openai", "openai_compatible".
+    pub provider: Option<String>,
+}
+
+/// Analyzer tuning: the thresholds that drive issue emission in
+/// `MetricsAnalyzer`, plus which analyzers `AnalysisEngine` should run.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AnalyzerConfigSection {
+    pub cyclomatic_complexity_threshold: Option<u32>,
+    pub cognitive_complexity_threshold: Option<u32>,
+    pub maintainability_index_threshold: Option<f32>,
+    pub duplication_threshold_percent: Option<f32>,
+    pub abc_size_warning_threshold: Option<f32>,
+    pub abc_size_error_threshold: Option<f32>,
+    /// Names of analyzers to register, e.g. `["metrics"]`. `None` means
+    /// "run everything `AnalysisEngine` knows about".
+    pub enabled: Option<Vec<String>>,
+    /// Directory of `.scm` rule files for `QueryAnalyzer` (see
+    /// `QueryAnalyzer::load_dir`). `None` means no custom rules are loaded,
+    /// even if `"query"` is enabled.
+    pub query_rules_dir: Option<PathBuf>,
+}
+
+impl AnalyzerConfigSection {
+    /// Merge this section's overrides onto the built-in defaults.
+    pub fn thresholds(&self) -> MetricsThresholds {
+        let defaults = MetricsThresholds::default();
+        MetricsThresholds {
+            cyclomatic_complexity: self
+                .cyclomatic_complexity_threshold
+                .unwrap_or(defaults.cyclomatic_complexity),
+            cognitive_complexity: self
+                .cognitive_complexity_threshold
+                .unwrap_or(defaults.cognitive_complexity),
+            maintainability_index: self
+                .maintainability_index_threshold
+                .unwrap_or(defaults.maintainability_index),
+            duplication_percentage: self
+                .duplication_threshold_percent
+                .unwrap_or(defaults.duplication_percentage),
+            abc_size_warning: self
+                .abc_size_warning_threshold
+                .unwrap_or(defaults.abc_size_warning),
+            abc_size_error: self
+                .abc_size_error_threshold
+                .unwrap_or(defaults.abc_size_error),
+        }
+    }
+
+    pub fn is_analyzer_enabled(&self, name: &str) -> bool {
+        match &self.enabled {
+            Some(enabled) => enabled.iter().any(|n| n == name),
+            None => true,
+        }
+    }
+}
+
+/// Per-category policy for the `Fix` command, e.g. whether issues in that
+/// category may be auto-applied without `--auto-apply`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FixPolicy {
+    pub auto_apply: bool,
+}
+
+/// Regexes that suppress issues before they reach any output format. See
+/// `crate::suppress`, which also honors a `.codesageignore` file and
+/// inline `// codesage:ignore <rule-id>` comments.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct IgnoreConfigSection {
+    /// Regexes matched against `Issue.location.file_path`.
+    pub paths: Vec<String>,
+    /// Regexes matched against `Issue.id`.
+    pub rules: Vec<String>,
+}
+
+/// Search upward from `start` (a file or directory) for `codesage.toml`
+/// and parse it, falling back to `Config::default()` if none is found.
+/// Unknown top-level keys are warned about rather than rejected, so older
+/// builds stay forward-compatible with newer config files.
+pub fn load(start: &Path) -> Result<Config> {
+    match find_config_file(start) {
+        Some(path) => parse_config_file(&path),
+        None => Ok(Config::default()),
+    }
+}
+
+fn find_config_file(start: &Path) -> Option<PathBuf> {
+    let mut dir = if start.is_dir() {
+        Some(start.to_path_buf())
+    } else {
+        start.parent().map(Path::to_path_buf)
+    };
+
+    while let Some(current) = dir {
+        let candidate = current.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = current.parent().map(Path::to_path_buf);
+    }
+
+    None
+}
+
+fn parse_config_file(path: &Path) -> Result<Config> {
+    let text = std::fs::read_to_string(path)?;
+
+    warn_on_unknown_keys(&text, path);
+
+    toml::from_str(&text)
+        .map_err(|e| CodeSageError::ConfigError(format!("{}: {}", path.display(), e)))
+}
+
+/// `#[serde(default)]` already makes missing keys forward-compatible; this
+/// guards the opposite mistake, a typo'd or renamed key that would
+/// otherwise be silently dropped on the floor.
+fn warn_on_unknown_keys(text: &str, path: &Path) {
+    const KNOWN_TOP_LEVEL: &[&str] = &["ai", "analyzer", "fix", "ignore"];
+
+    let Ok(toml::Value::Table(table)) = text.parse::<toml::Value>() else {
+        return; // the real parse below reports syntax errors
+    };
+
+    for key in table.keys() {
+        if !KNOWN_TOP_LEVEL.contains(&key.as_str()) {
+            eprintln!("Warning: unknown key `{}` in {}", key, path.display());
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Unified diff parsing and application, used by the `Fix` command to turn
+//! an `Issue`'s `Fix::diff` into an on-disk edit.
+//!
+//! Hunks are matched against the target file primarily by their recorded
+//! line number, but the file may have drifted since the fix was generated,
+//! so a hunk that doesn't match at its recorded offset is retried at
+//! nearby offsets before being given up on.
+
+/// How far `apply_hunks` will search, in either direction, for a hunk's
+/// context when it doesn't match at the recorded offset.
+const FUZZY_SEARCH_RADIUS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffLine {
+    Context(String),
+    Add(String),
+    Remove(String),
+}
+
+/// One `@@ ... @@` hunk: the line it expects to start at in the original
+/// file, plus its context/add/remove lines in order.
+#[derive(Debug, Clone)]
+pub(crate) struct Hunk {
+    old_start: usize,
+    lines: Vec<DiffLine>,
+}
+
+impl Hunk {
+    /// The lines this hunk expects to find in the original file (context +
+    /// removed).
+    fn original_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                DiffLine::Context(s) | DiffLine::Remove(s) => Some(s.as_str()),
+                DiffLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    /// The lines this hunk produces (context + added).
+    fn replacement_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                DiffLine::Context(s) | DiffLine::Add(s) => Some(s.as_str()),
+                DiffLine::Remove(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Parse a unified diff (`---`/`+++` file headers are skipped if present)
+/// into its hunks.
+pub(crate) fn parse(diff: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks = Vec::new();
+    let mut lines = diff.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("---") || line.starts_with("+++") {
+            continue;
+        }
+        let Some(header) = line.strip_prefix("@@ ") else {
+            continue;
+        };
+        let old_start =
+            parse_hunk_header(header).ok_or_else(|| format!("malformed hunk header: {}", line))?;
+
+        let mut hunk_lines = Vec::new();
+        while let Some(&next) = lines.peek() {
+            if next.starts_with("@@ ") {
+                break;
+            }
+            let line = lines.next().unwrap();
+            if let Some(rest) = line.strip_prefix('+') {
+                hunk_lines.push(DiffLine::Add(rest.to_string()));
+            } else if let Some(rest) = line.strip_prefix('-') {
+                hunk_lines.push(DiffLine::Remove(rest.to_string()));
+            } else {
+                hunk_lines.push(DiffLine::Context(line.strip_prefix(' ').unwrap_or(line).to_string()));
+            }
+        }
+
+        hunks.push(Hunk {
+            old_start,
+            lines: hunk_lines,
+        });
+    }
+
+    if hunks.is_empty() {
+        return Err("diff contains no hunks".to_string());
+    }
+
+    Ok(hunks)
+}
+
+/// Parse the `-a,b +c,d` portion of a `@@ -a,b +c,d @@` header, returning
+/// the original file's starting line `a`.
+fn parse_hunk_header(header: &str) -> Option<usize> {
+    let old_range = header.split('+').next()?.trim();
+    let old_range = old_range.strip_prefix('-')?;
+    old_range.split(',').next()?.trim().parse().ok()
+}
+
+/// Apply every hunk to `original`, returning the patched text only if every
+/// hunk applies cleanly (all-or-nothing, so a file is never left
+/// half-patched). Each hunk is first tried at its recorded line, then at
+/// progressively wider offsets up to `FUZZY_SEARCH_RADIUS` lines away, to
+/// tolerate the file having moved since the fix was generated.
+pub(crate) fn apply_hunks(original: &str, hunks: &[Hunk]) -> Option<String> {
+    let mut lines: Vec<String> = original.lines().map(str::to_string).collect();
+    let had_trailing_newline = original.ends_with('\n') || original.is_empty();
+
+    // Apply from the bottom of the file up, so an earlier hunk's recorded
+    // line number isn't invalidated by a later hunk adding or removing
+    // lines above it.
+    let mut ordered: Vec<&Hunk> = hunks.iter().collect();
+    ordered.sort_by_key(|h| std::cmp::Reverse(h.old_start));
+
+    for hunk in ordered {
+        let anchor = find_anchor(&lines, hunk)?;
+        let replacement: Vec<String> = hunk
+            .replacement_lines()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        lines.splice(anchor..anchor + hunk.original_lines().len(), replacement);
+    }
+
+    let mut patched = lines.join("\n");
+    if had_trailing_newline && !patched.is_empty() {
+        patched.push('\n');
+    }
+    Some(patched)
+}
+
+/// Find the 0-based line index where `hunk`'s original lines actually
+/// match `lines`, starting from its recorded position and fanning out by
+/// up to `FUZZY_SEARCH_RADIUS` lines in either direction.
+fn find_anchor(lines: &[String], hunk: &Hunk) -> Option<usize> {
+    let wanted = hunk.original_lines();
+    let recorded = hunk.old_start.saturating_sub(1);
+
+    if matches_at(lines, recorded, &wanted) {
+        return Some(recorded);
+    }
+
+    for offset in 1..=FUZZY_SEARCH_RADIUS {
+        if recorded >= offset && matches_at(lines, recorded - offset, &wanted) {
+            return Some(recorded - offset);
+        }
+        if matches_at(lines, recorded + offset, &wanted) {
+            return Some(recorded + offset);
+        }
+    }
+
+    None
+}
+
+fn matches_at(lines: &[String], start: usize, wanted: &[&str]) -> bool {
+    if start + wanted.len() > lines.len() {
+        return false;
+    }
+    lines[start..start + wanted.len()]
+        .iter()
+        .zip(wanted)
+        .all(|(have, want)| have == want)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_hunk() {
+        let diff = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    println!(\"old\");\n+    println!(\"new\");\n }\n";
+        let hunks = parse(diff).unwrap();
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_start, 1);
+        assert_eq!(
+            hunks[0].original_lines(),
+            vec!["fn main() {", "    println!(\"old\");", "}"]
+        );
+        assert_eq!(
+            hunks[0].replacement_lines(),
+            vec!["fn main() {", "    println!(\"new\");", "}"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_diff_with_no_hunks() {
+        let diff = "--- a/src/lib.rs\n+++ b/src/lib.rs\n";
+        assert!(parse(diff).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_hunk_header() {
+        let diff = "@@ garbage @@\n context\n";
+        assert!(parse(diff).is_err());
+    }
+
+    #[test]
+    fn test_apply_hunks_at_recorded_offset() {
+        let original = "fn main() {\n    println!(\"old\");\n}\n";
+        let diff = "@@ -1,3 +1,3 @@\n fn main() {\n-    println!(\"old\");\n+    println!(\"new\");\n }\n";
+        let hunks = parse(diff).unwrap();
+
+        let patched = apply_hunks(original, &hunks).unwrap();
+        assert_eq!(patched, "fn main() {\n    println!(\"new\");\n}\n");
+    }
+
+    /// A hunk recorded at a stale line number should still apply, as long
+    /// as its context is found within `FUZZY_SEARCH_RADIUS` lines.
+    #[test]
+    fn test_apply_hunks_finds_shifted_anchor() {
+        let original = "// a leading comment\n// that shifted everything down\nfn main() {\n    println!(\"old\");\n}\n";
+        // Recorded as if the file still started at line 1.
+        let diff = "@@ -1,3 +1,3 @@\n fn main() {\n-    println!(\"old\");\n+    println!(\"new\");\n }\n";
+        let hunks = parse(diff).unwrap();
+
+        let patched = apply_hunks(original, &hunks).unwrap();
+        assert_eq!(
+            patched,
+            "// a leading comment\n// that shifted everything down\nfn main() {\n    println!(\"new\");\n}\n"
+        );
+    }
+
+    /// All-or-nothing: if any hunk's context can't be found, nothing is
+    /// patched, even if other hunks in the same diff would have applied.
+    #[test]
+    fn test_apply_hunks_is_all_or_nothing() {
+        let original = "fn main() {\n    println!(\"old\");\n}\n";
+        // The first hunk matches on its own; the second one's context doesn't.
+        let diff = "@@ -1,1 +1,1 @@\n-fn main() {\n+fn start() {\n@@ -2,1 +2,1 @@\n-    println!(\"does not exist\");\n+    println!(\"new\");\n";
+        let hunks = parse(diff).unwrap();
+
+        assert_eq!(hunks.len(), 2);
+        assert!(apply_hunks(original, &hunks).is_none());
+    }
+}
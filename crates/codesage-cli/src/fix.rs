@@ -0,0 +1,199 @@
+//! The `Fix` command: apply an `Issue`'s `Fix::diff` directly to disk.
+//!
+//! Issues are collected the same way `Review` collects them (parse, run
+//! `AnalysisEngine`); whichever of them carry a `fix_suggestion` are then
+//! grouped by file and applied one at a time via `crate::diff`.
+//! `--auto-apply` applies only fixes marked `safe_to_auto_apply` and skips
+//! the rest; without it, each fix is shown to the user as a diff and
+//! applied only on confirmation.
+
+use crate::diff;
+use crate::{build_analysis_engine, collect_source_files, config};
+use codesage_core::{AnalysisContext, Fix, Issue, Result};
+use codesage_parser::CodeParser;
+use colored::Colorize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Tally of what happened to each fixable issue, printed once at the end.
+#[derive(Default)]
+struct FixReport {
+    applied: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+pub fn handle_fix(path: String, category: Option<String>, auto_apply: bool) -> Result<()> {
+    let path_buf = PathBuf::from(&path);
+    let config = config::load(&path_buf)?;
+
+    println!("{} Fixing issues in: {}", "🔧".magenta(), path.bold());
+    if let Some(cat) = &category {
+        println!("   Category: {}", cat);
+    }
+
+    let auto_apply = auto_apply
+        || category
+            .as_ref()
+            .and_then(|cat| config.fix.get(cat))
+            .is_some_and(|policy| policy.auto_apply);
+    println!("   Auto-apply: {}", auto_apply);
+
+    let files = if path_buf.is_dir() {
+        collect_source_files(&path_buf)
+    } else {
+        vec![path_buf.clone()]
+    };
+
+    if files.is_empty() {
+        println!("\n{} No source files found!", "⚠".yellow().bold());
+        return Ok(());
+    }
+
+    let mut parser = CodeParser::new();
+    let mut issues_by_file: HashMap<PathBuf, Vec<Issue>> = HashMap::new();
+
+    for file_path in &files {
+        let Ok(parsed) = parser.parse_file(file_path) else {
+            continue;
+        };
+        let context = AnalysisContext {
+            file_path: file_path.clone(),
+            source_code: parsed.source().to_string(),
+            language: parsed.language,
+        };
+
+        let engine = build_analysis_engine(&config, parsed.language);
+
+        let Ok(issues) = engine.analyze(&context) else {
+            continue;
+        };
+        let fixable: Vec<Issue> = issues
+            .into_iter()
+            .filter(|issue| category_matches(issue, category.as_deref()))
+            .filter(|issue| issue.fix_suggestion.is_some())
+            .collect();
+
+        if !fixable.is_empty() {
+            issues_by_file.insert(file_path.clone(), fixable);
+        }
+    }
+
+    if issues_by_file.is_empty() {
+        println!("\n{} No fixable issues found!", "✓".green().bold());
+        return Ok(());
+    }
+
+    let mut report = FixReport::default();
+    for (file_path, issues) in &issues_by_file {
+        apply_fixes_to_file(file_path, issues, auto_apply, &mut report);
+    }
+
+    println!("\n{}", "Fix summary:".bold().underline());
+    println!("  Applied: {}", report.applied.to_string().green());
+    println!("  Skipped: {}", report.skipped.to_string().yellow());
+    println!("  Failed:  {}", report.failed.to_string().red());
+
+    Ok(())
+}
+
+/// Whether `issue` belongs to the `--category` filter (case-insensitive
+/// match against the `IssueCategory` variant name). No filter means every
+/// category matches.
+fn category_matches(issue: &Issue, category: Option<&str>) -> bool {
+    match category {
+        None => true,
+        Some(cat) => format!("{:?}", issue.category).eq_ignore_ascii_case(cat),
+    }
+}
+
+/// Apply every fixable issue's diff to a single file, one at a time: each
+/// fix is read and written against the file's current on-disk state, so a
+/// later fix in the same file sees any earlier one already applied.
+fn apply_fixes_to_file(file_path: &Path, issues: &[Issue], auto_apply: bool, report: &mut FixReport) {
+    for issue in issues {
+        let Some(fix) = &issue.fix_suggestion else {
+            continue;
+        };
+
+        let hunks = match diff::parse(&fix.diff) {
+            Ok(hunks) => hunks,
+            Err(e) => {
+                eprintln!(
+                    "{} Could not parse diff for {} in {}: {}",
+                    "✗".red(),
+                    issue.id,
+                    file_path.display(),
+                    e
+                );
+                report.failed += 1;
+                continue;
+            }
+        };
+
+        let Ok(original) = std::fs::read_to_string(file_path) else {
+            eprintln!("{} Could not read {}", "✗".red(), file_path.display());
+            report.failed += 1;
+            continue;
+        };
+
+        let Some(patched) = diff::apply_hunks(&original, &hunks) else {
+            eprintln!(
+                "{} Fix for {} in {} did not apply cleanly (context mismatch)",
+                "✗".red(),
+                issue.id,
+                file_path.display()
+            );
+            report.failed += 1;
+            continue;
+        };
+
+        let should_apply = if auto_apply {
+            fix.safe_to_auto_apply
+        } else {
+            prompt_to_apply(file_path, issue, fix)
+        };
+
+        if !should_apply {
+            report.skipped += 1;
+            continue;
+        }
+
+        match std::fs::write(file_path, patched) {
+            Ok(()) => {
+                println!(
+                    "{} Applied {} to {}",
+                    "✓".green(),
+                    issue.id.bold(),
+                    file_path.display()
+                );
+                report.applied += 1;
+            }
+            Err(e) => {
+                eprintln!("{} Failed to write {}: {}", "✗".red(), file_path.display(), e);
+                report.failed += 1;
+            }
+        }
+    }
+}
+
+/// Show an issue's diff and ask the user whether to apply it.
+fn prompt_to_apply(file_path: &Path, issue: &Issue, fix: &Fix) -> bool {
+    println!(
+        "\n{} [{}] {} ({})",
+        "?".cyan().bold(),
+        issue.id,
+        fix.description,
+        file_path.display()
+    );
+    println!("{}", fix.diff);
+    print!("Apply this fix? [y/N] ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -2,12 +2,19 @@
 //!
 //! This module provides the CLI entry point and command handlers
 
+mod config;
+mod diff;
+mod fix;
+mod refactor;
+mod suppress;
+
 use clap::{Parser, Subcommand};
-use codesage_analyzer::{AnalysisEngine, MetricsAnalyzer};
+use codesage_analyzer::{AnalysisEngine, MetricsAnalyzer, QueryAnalyzer, SyntaxAnalyzer};
 use codesage_core::{AnalysisContext, Issue, Result};
 use codesage_parser::CodeParser;
+use codesage_walk::Walker;
 use colored::Colorize;
-use ignore::WalkBuilder;
+use config::Config;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use serde::Serialize;
@@ -34,13 +41,40 @@ enum Commands {
         #[arg(short, long)]
         recursive: bool,
 
-        /// Output format (text, json, sarif)
+        /// Output format (text, json, sarif, gitlab)
         #[arg(short, long, default_value = "text")]
         format: String,
 
         /// Enable AI-powered review (requires ANTHROPIC_API_KEY)
         #[arg(long)]
         ai: bool,
+
+        /// Override the AI model to use for a single-file review (takes
+        /// precedence over codesage.toml); a recursive review always uses
+        /// codesage.toml's model or the default
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Maximum number of AI review requests to have in flight at once
+        /// in recursive mode
+        #[arg(long, default_value_t = 3)]
+        ai_concurrency: usize,
+
+        /// Prior SARIF report to diff against; matching results are marked
+        /// `baselineState: "unchanged"`, everything else `"new"` (sarif
+        /// format only)
+        #[arg(long)]
+        baseline: Option<String>,
+
+        /// Exit with a non-zero status if any result is new relative to
+        /// `--baseline` (sarif format only)
+        #[arg(long)]
+        fail_on_new: bool,
+
+        /// Semantic index database (built via `codesage index`) to pull
+        /// related code from as extra context for `--ai` review
+        #[arg(long)]
+        index: Option<String>,
     },
 
     /// Perform intelligent refactoring
@@ -48,9 +82,30 @@ enum Commands {
         /// Path to file to refactor
         path: String,
 
-        /// Interactive mode
+        /// Interactive mode: review and confirm each suggestion before
+        /// it's written, rather than applying every match found
         #[arg(short, long)]
         interactive: bool,
+
+        /// Structured Search and Replace pattern, e.g. "foo($a, $b)".
+        /// Must be given together with --replace.
+        #[arg(long)]
+        pattern: Option<String>,
+
+        /// Structured Search and Replace replacement template, e.g.
+        /// "bar($b, $a)". Must be given together with --pattern.
+        #[arg(long)]
+        replace: Option<String>,
+
+        /// Extract Method: a `START:END` line range (1-indexed, inclusive)
+        /// to pull into a new function.
+        #[arg(long)]
+        extract: Option<String>,
+
+        /// Name for the function generated by --extract. Defaults to
+        /// "extracted".
+        #[arg(long)]
+        name: Option<String>,
     },
 
     /// Generate technical debt report
@@ -61,6 +116,10 @@ enum Commands {
         /// Output HTML report
         #[arg(long)]
         output_html: Option<String>,
+
+        /// Output format (text, dot)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
 
     /// Fix issues automatically
@@ -76,6 +135,16 @@ enum Commands {
         #[arg(long)]
         auto_apply: bool,
     },
+
+    /// Build or refresh the semantic code index used for retrieval-augmented review
+    Index {
+        /// Path to the directory to index
+        path: String,
+
+        /// Path to the SQLite index database
+        #[arg(long, default_value = ".codesage/index.sqlite")]
+        db: String,
+    },
 }
 
 /// Main CLI entry point
@@ -90,51 +159,195 @@ pub async fn run() -> Result<()> {
             recursive,
             format,
             ai,
+            model,
+            ai_concurrency,
+            baseline,
+            fail_on_new,
+            index,
         } => {
-            handle_review(path, recursive, format, ai).await?;
+            handle_review(
+                path,
+                recursive,
+                format,
+                ai,
+                model,
+                ai_concurrency,
+                baseline,
+                fail_on_new,
+                index,
+            )
+            .await?;
         }
-        Commands::Refactor { path, interactive } => {
-            println!("{} Refactoring: {}", "♻️".green(), path.bold());
-            println!("   Interactive: {}", interactive);
-            println!("\n{} Refactoring feature coming soon!", "⚠️".yellow());
+        Commands::Refactor {
+            path,
+            interactive,
+            pattern,
+            replace,
+            extract,
+            name,
+        } => {
+            if let Some(range) = extract {
+                refactor::handle_extract_method(path, range, name)?;
+            } else {
+                match (pattern, replace) {
+                    (Some(pattern), Some(replace)) => {
+                        refactor::handle_refactor(path, interactive, pattern, replace)?;
+                    }
+                    (None, None) => {
+                        println!("{} Refactoring: {}", "♻️".green(), path.bold());
+                        println!("   Interactive: {}", interactive);
+                        println!("\n{} Refactoring feature coming soon!", "⚠️".yellow());
+                    }
+                    _ => {
+                        eprintln!(
+                            "{} --pattern and --replace must be given together",
+                            "✗".red()
+                        );
+                    }
+                }
+            }
         }
-        Commands::Debt { path, output_html } => {
-            println!("{} Analyzing technical debt: {}", "📊".blue(), path.bold());
-            if let Some(output) = output_html {
-                println!("   Output: {}", output);
+        Commands::Debt {
+            path,
+            output_html,
+            format,
+        } => {
+            if format == "dot" {
+                print_call_graph(PathBuf::from(&path))?;
+            } else {
+                println!("{} Analyzing technical debt: {}", "📊".blue(), path.bold());
+                if let Some(output) = output_html {
+                    println!("   Output: {}", output);
+                }
+                println!("\n{} Debt analysis feature coming soon!", "⚠️".yellow());
             }
-            println!("\n{} Debt analysis feature coming soon!", "⚠️".yellow());
         }
         Commands::Fix {
             path,
             category,
             auto_apply,
         } => {
-            println!("{} Fixing issues in: {}", "🔧".magenta(), path.bold());
-            if let Some(cat) = category {
-                println!("   Category: {}", cat);
+            fix::handle_fix(path, category, auto_apply)?;
+        }
+        Commands::Index { path, db } => {
+            handle_index(path, db).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Build or refresh the semantic code index
+async fn handle_index(path: String, db: String) -> Result<()> {
+    use codesage_ai::{AIClient, SemanticIndex};
+
+    let dir_path = PathBuf::from(&path);
+    let db_path = PathBuf::from(&db);
+    let config = config::load(&dir_path)?;
+    let ai_client = AIClient::with_config(build_ai_config(&config, None));
+
+    println!(
+        "{} Indexing: {} -> {}",
+        "🗂️".cyan(),
+        dir_path.display().to_string().bold(),
+        db_path.display()
+    );
+
+    let files = collect_source_files(&dir_path);
+    if files.is_empty() {
+        println!("\n{} No source files found!", "⚠".yellow().bold());
+        return Ok(());
+    }
+
+    let index = SemanticIndex::open(&db_path)?;
+
+    let progress = ProgressBar::new(files.len() as u64);
+    progress.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+            .expect("Invalid progress bar template")
+            .progress_chars("=>-"),
+    );
+
+    let mut chunks_indexed = 0usize;
+    for file_path in &files {
+        progress.set_message(format!(
+            "Indexing {}",
+            file_path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+
+        if let Ok(source) = std::fs::read_to_string(file_path) {
+            match index.index_file(&ai_client, file_path, &source).await {
+                Ok(count) => chunks_indexed += count,
+                Err(e) => eprintln!(
+                    "{} Failed to index {}: {}",
+                    "⚠".yellow(),
+                    file_path.display(),
+                    e
+                ),
             }
-            println!("   Auto-apply: {}", auto_apply);
-            println!("\n{} Auto-fix feature coming soon!", "⚠️".yellow());
         }
+
+        progress.inc(1);
     }
+    progress.finish_with_message("Indexing complete");
+
+    println!(
+        "\n{} Indexed {} file(s), {} chunk(s) (re)embedded",
+        "✓".green().bold(),
+        files.len(),
+        chunks_indexed
+    );
 
     Ok(())
 }
 
 /// Handle the review command
-async fn handle_review(path: String, recursive: bool, format: String, use_ai: bool) -> Result<()> {
+async fn handle_review(
+    path: String,
+    recursive: bool,
+    format: String,
+    use_ai: bool,
+    model: Option<String>,
+    ai_concurrency: usize,
+    baseline: Option<String>,
+    fail_on_new: bool,
+    index: Option<String>,
+) -> Result<()> {
     let path_buf = PathBuf::from(&path);
+    let config = config::load(&path_buf)?;
 
     if recursive && path_buf.is_dir() {
-        handle_recursive_review(path_buf, format, use_ai).await
+        handle_recursive_review(
+            path_buf,
+            format,
+            use_ai,
+            config,
+            ai_concurrency,
+            baseline,
+            fail_on_new,
+            index,
+        )
+        .await
     } else {
-        handle_single_file_review(path_buf, format, use_ai).await
+        handle_single_file_review(
+            path_buf, format, use_ai, model, config, baseline, fail_on_new, index,
+        )
+        .await
     }
 }
 
 /// Handle review of a single file
-async fn handle_single_file_review(file_path: PathBuf, format: String, use_ai: bool) -> Result<()> {
+async fn handle_single_file_review(
+    file_path: PathBuf,
+    format: String,
+    use_ai: bool,
+    model: Option<String>,
+    config: Config,
+    baseline: Option<String>,
+    fail_on_new: bool,
+    index: Option<String>,
+) -> Result<()> {
     println!(
         "{} Reviewing code at: {}",
         "🔍".cyan(),
@@ -142,7 +355,7 @@ async fn handle_single_file_review(file_path: PathBuf, format: String, use_ai: b
     );
 
     // Parse the file
-    let parser = CodeParser::new();
+    let mut parser = CodeParser::new();
     let parsed = parser.parse_file(&file_path)?;
 
     println!("\n{}", "Analysis Results:".bold().underline());
@@ -156,25 +369,60 @@ async fn handle_single_file_review(file_path: PathBuf, format: String, use_ai: b
         language: parsed.language,
     };
 
-    // Run static analysis
-    let mut engine = AnalysisEngine::new();
-    engine.register_analyzer(Box::new(MetricsAnalyzer::new()));
+    if format == "dot" {
+        let graph = codesage_analyzer::build_call_graph(&context);
+        println!("\n{}", graph.to_dot());
+        return Ok(());
+    }
 
+    // Run static analysis
+    let engine = build_analysis_engine(&config, parsed.language);
     let issues = engine.analyze(&context)?;
 
+    let ignore_rules = suppress::IgnoreRules::load(&file_path, &config.ignore);
+    let (issues, suppressed) = suppress::filter(issues, &ignore_rules);
+    if suppressed > 0 {
+        println!("  Suppressed: {}", suppressed);
+    }
+
     // Display results based on format
-    display_results(&issues, &format);
+    display_results(&issues, &format, baseline.as_deref(), fail_on_new);
 
     // AI review if enabled
     if use_ai {
-        run_ai_review(&context).await;
+        run_ai_review(&context, &config, model, index.as_deref()).await;
     }
 
     Ok(())
 }
 
+/// Build and print the call graph for a single file (used by `Debt --format dot`)
+fn print_call_graph(file_path: PathBuf) -> Result<()> {
+    let mut parser = CodeParser::new();
+    let parsed = parser.parse_file(&file_path)?;
+
+    let context = AnalysisContext {
+        file_path,
+        source_code: parsed.source().to_string(),
+        language: parsed.language,
+    };
+
+    let graph = codesage_analyzer::build_call_graph(&context);
+    println!("{}", graph.to_dot());
+    Ok(())
+}
+
 /// Handle recursive review of a directory
-async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool) -> Result<()> {
+async fn handle_recursive_review(
+    dir_path: PathBuf,
+    format: String,
+    use_ai: bool,
+    config: Config,
+    ai_concurrency: usize,
+    baseline: Option<String>,
+    fail_on_new: bool,
+    index: Option<String>,
+) -> Result<()> {
     println!(
         "{} Recursively reviewing directory: {}",
         "🔍".cyan(),
@@ -182,7 +430,7 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
     );
 
     // Collect all source files
-    let files = collect_source_files(&dir_path)?;
+    let files = collect_source_files(&dir_path);
 
     if files.is_empty() {
         println!("\n{} No source files found!", "⚠".yellow().bold());
@@ -202,9 +450,15 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
 
     // Analyze files in parallel
     let all_issues = Arc::new(Mutex::new(Vec::new()));
-    let parser = CodeParser::new();
-
-    files.par_iter().for_each(|file_path| {
+    let sources_for_duplication = Arc::new(Mutex::new(Vec::new()));
+    let ai_contexts = Arc::new(Mutex::new(Vec::new()));
+
+    // Each worker owns its own `CodeParser` (seeded via `for_each_init`)
+    // rather than sharing one across the closure: `parse_file` takes
+    // `&mut self`, and `ParallelIterator::for_each` requires `Fn`, not
+    // `FnMut`, so a single shared parser can't be mutably borrowed from
+    // multiple threads.
+    files.par_iter().for_each_init(CodeParser::new, |parser, file_path| {
         progress.set_message(format!(
             "Analyzing {}",
             file_path.file_name().unwrap_or_default().to_string_lossy()
@@ -217,8 +471,7 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
                 language: parsed.language,
             };
 
-            let mut engine = AnalysisEngine::new();
-            engine.register_analyzer(Box::new(MetricsAnalyzer::new()));
+            let engine = build_analysis_engine(&config, parsed.language);
 
             if let Ok(issues) = engine.analyze(&context)
                 && !issues.is_empty()
@@ -226,6 +479,20 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
                 let mut all = all_issues.lock().unwrap();
                 all.extend(issues);
             }
+
+            if config.analyzer.is_analyzer_enabled("metrics") {
+                sources_for_duplication
+                    .lock()
+                    .unwrap()
+                    .push(codesage_analyzer::DuplicationSource {
+                        file_path: context.file_path.clone(),
+                        source_code: context.source_code.clone(),
+                    });
+            }
+
+            if use_ai {
+                ai_contexts.lock().unwrap().push(context);
+            }
         }
 
         progress.inc(1);
@@ -233,12 +500,35 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
 
     progress.finish_with_message("Analysis complete");
 
-    let issues = all_issues.lock().unwrap();
+    // Clone detection across file boundaries can't run per-file, so it's a
+    // separate pass once every file's source has been collected.
+    let cross_file_duplicates = codesage_analyzer::detect_duplication_across_files(
+        &sources_for_duplication.lock().unwrap(),
+    );
+    all_issues.lock().unwrap().extend(cross_file_duplicates);
+
+    // AI review runs after static analysis so its issues flow through the
+    // same severity tally, suppression and display path.
+    let mut ai_summary = String::new();
+    if use_ai {
+        let contexts = std::mem::take(&mut *ai_contexts.lock().unwrap());
+        let (ai_issues, summary) =
+            run_batch_ai_review(contexts, &config, ai_concurrency, index.as_deref()).await;
+        all_issues.lock().unwrap().extend(ai_issues);
+        ai_summary = summary;
+    }
+
+    let issues = std::mem::take(&mut *all_issues.lock().unwrap());
+    let ignore_rules = suppress::IgnoreRules::load(&dir_path, &config.ignore);
+    let (issues, suppressed) = suppress::filter(issues, &ignore_rules);
 
     // Display aggregated results
     println!("\n{}", "Summary:".bold().underline());
     println!("  Files analyzed: {}", files.len());
     println!("  Total issues found: {}", issues.len());
+    if suppressed > 0 {
+        println!("  Suppressed: {}", suppressed);
+    }
 
     if !issues.is_empty() {
         // Group issues by severity
@@ -270,58 +560,161 @@ async fn handle_recursive_review(dir_path: PathBuf, format: String, use_ai: bool
             println!("  P3 (Low): {}", p3_count);
         }
 
-        display_results(&issues, &format);
+        display_results(&issues, &format, baseline.as_deref(), fail_on_new);
     } else {
         println!("\n{} No issues found!", "✓".green().bold());
     }
 
-    // AI review for recursive mode
-    if use_ai {
-        println!(
-            "\n{} AI review is not yet supported for recursive mode",
-            "⚠".yellow()
-        );
-        println!("   Tip: Use --ai with single file review for AI-powered insights");
+    if !ai_summary.is_empty() {
+        println!("\n{}", "Cross-file AI summary:".bold().underline());
+        println!("{}", ai_summary);
     }
 
     Ok(())
 }
 
-/// Collect all source files from a directory, respecting .gitignore
-fn collect_source_files(dir: &PathBuf) -> Result<Vec<PathBuf>> {
-    let mut files = Vec::new();
+/// Run AI review across every collected context, with at most
+/// `concurrency` requests in flight at once, then ask the model for a
+/// cross-file summary of recurring patterns across whatever it found. A
+/// single file's failure (missing API key, timeout, malformed response) is
+/// logged and skipped rather than aborting the batch.
+async fn run_batch_ai_review(
+    contexts: Vec<AnalysisContext>,
+    config: &Config,
+    concurrency: usize,
+    index_db: Option<&str>,
+) -> (Vec<Issue>, String) {
+    use codesage_ai::{AIClient, SemanticIndex};
+    use codesage_core::AIReviewer;
+    use futures::stream::{self, StreamExt};
 
-    // Supported extensions
-    let supported_extensions = vec![
-        "rs", "js", "ts", "jsx", "tsx", "py", "go", "java", "cpp", "cc", "cxx", "c", "cs",
-    ];
+    if contexts.is_empty() {
+        return (Vec::new(), String::new());
+    }
 
-    for result in WalkBuilder::new(dir)
-        .hidden(false) // Include hidden files
-        .git_ignore(true) // Respect .gitignore
-        .build()
-    {
-        match result {
-            Ok(entry) => {
-                let path = entry.path();
-                if path.is_file()
-                    && let Some(ext) = path.extension()
-                    && supported_extensions.contains(&ext.to_string_lossy().as_ref())
-                {
-                    files.push(path.to_path_buf());
+    let concurrency = concurrency.max(1);
+    println!(
+        "\n{} Running AI review across {} file(s) ({} concurrent)...",
+        "🤖".cyan(),
+        contexts.len(),
+        concurrency
+    );
+
+    let ai_client = AIClient::with_config(build_ai_config(config, None));
+    let index = match index_db {
+        Some(db) => match SemanticIndex::open(&PathBuf::from(db)) {
+            Ok(index) => Some(index),
+            Err(e) => {
+                eprintln!("{} Could not open semantic index {}: {}", "⚠".yellow(), db, e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let per_file: Vec<Vec<Issue>> = stream::iter(&contexts)
+        .map(|context| {
+            let ai_client = &ai_client;
+            let index = &index;
+            async move {
+                let result = match index {
+                    Some(index) => ai_client.review_with_index(context, index).await,
+                    None => ai_client.review(context).await,
+                };
+                match result {
+                    Ok(review_result) => review_result.issues,
+                    Err(e) => {
+                        eprintln!(
+                            "{} AI review unavailable for {}: {}",
+                            "⚠".yellow(),
+                            context.file_path.display(),
+                            e
+                        );
+                        Vec::new()
+                    }
                 }
             }
-            Err(err) => {
-                eprintln!("{} Error walking directory: {}", "⚠".yellow(), err);
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    let issues: Vec<Issue> = per_file.into_iter().flatten().collect();
+    println!(
+        "{} AI review complete: {} additional issue(s)",
+        "✓".green(),
+        issues.len()
+    );
+
+    let summary = if issues.is_empty() {
+        String::new()
+    } else {
+        match ai_client.summarize_findings(&issues).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                eprintln!(
+                    "{} Could not generate cross-file AI summary: {}",
+                    "⚠".yellow(),
+                    e
+                );
+                String::new()
             }
         }
+    };
+
+    (issues, summary)
+}
+
+/// Build the `AnalysisEngine` every review/fix entry point should use for a
+/// file written in `language`: `MetricsAnalyzer`, `QueryAnalyzer` and
+/// `SyntaxAnalyzer`, each gated by `config.analyzer.is_analyzer_enabled`.
+/// Centralized here so the three analyzers stay in sync across every
+/// construction site instead of drifting as each is added separately.
+fn build_analysis_engine(config: &Config, language: codesage_core::Language) -> AnalysisEngine {
+    let mut engine = AnalysisEngine::new();
+
+    if config.analyzer.is_analyzer_enabled("metrics") {
+        engine.register_analyzer(Box::new(MetricsAnalyzer::with_thresholds(
+            config.analyzer.thresholds(),
+        )));
     }
 
-    Ok(files)
+    if config.analyzer.is_analyzer_enabled("syntax") {
+        engine.register_analyzer(Box::new(SyntaxAnalyzer::new()));
+    }
+
+    if config.analyzer.is_analyzer_enabled("query")
+        && let Some(rules_dir) = &config.analyzer.query_rules_dir
+    {
+        match QueryAnalyzer::load_dir(language, rules_dir) {
+            Ok(analyzer) => engine.register_analyzer(Box::new(analyzer)),
+            Err(err) => eprintln!(
+                "{} Failed to load query rules from {}: {}",
+                "⚠".yellow(),
+                rules_dir.display(),
+                err
+            ),
+        }
+    }
+
+    engine
+}
+
+/// Collect all source files from a directory, respecting .gitignore.
+/// Delegates to `codesage_walk::Walker`, the same ignore-aware traversal
+/// `codesage-walk`'s `Dispatcher`/`Reporter` pipeline uses, instead of a
+/// second hand-rolled extension list here - this crate just doesn't reuse
+/// `Walker`'s paired `Dispatcher`/`Reporter` too, since the duplication
+/// detection, AI review and suppression/baseline handling in
+/// `handle_recursive_review` have no equivalent there yet.
+fn collect_source_files(dir: &PathBuf) -> Vec<PathBuf> {
+    Walker::walk(dir).into_iter().map(|file| file.path).collect()
 }
 
-/// Display analysis results in the requested format
-fn display_results(issues: &[Issue], format: &str) {
+/// Display analysis results in the requested format. `baseline` and
+/// `fail_on_new` only apply to the sarif format: everything else ignores
+/// them.
+fn display_results(issues: &[Issue], format: &str, baseline: Option<&str>, fail_on_new: bool) {
     match format {
         "json" => {
             let json = serde_json::to_string_pretty(&issues)
@@ -329,10 +722,29 @@ fn display_results(issues: &[Issue], format: &str) {
             println!("\n{}", json);
         }
         "sarif" => {
-            let sarif = convert_to_sarif(issues);
+            let mut sarif = convert_to_sarif(issues);
+            let new_count = baseline
+                .map(|path| apply_sarif_baseline(&mut sarif, path))
+                .unwrap_or(0);
+
             let json = serde_json::to_string_pretty(&sarif)
                 .unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
             println!("{}", json);
+
+            if fail_on_new && new_count > 0 {
+                eprintln!(
+                    "\n{} {} new issue(s) since baseline",
+                    "✗".red().bold(),
+                    new_count
+                );
+                std::process::exit(1);
+            }
+        }
+        "gitlab" => {
+            let report = convert_to_gitlab_code_quality(issues);
+            let json = serde_json::to_string_pretty(&report)
+                .unwrap_or_else(|e| format!("{{\"error\": \"{}\"}}", e));
+            println!("{}", json);
         }
         "text" => {
             display_text_results(issues);
@@ -368,15 +780,35 @@ fn display_text_results(issues: &[Issue]) {
     }
 }
 
-/// Run AI-powered review
-async fn run_ai_review(context: &AnalysisContext) {
+/// Run AI-powered review. When `index_db` points at a semantic index built
+/// by `codesage index`, the review is retrieval-augmented with related
+/// chunks from the rest of the project via `AIClient::review_with_index`
+/// instead of the plain `AIReviewer::review`.
+async fn run_ai_review(
+    context: &AnalysisContext,
+    config: &Config,
+    model_override: Option<String>,
+    index_db: Option<&str>,
+) {
     println!("\n{} Running AI-powered review...", "🤖".cyan());
 
-    use codesage_ai::{AIClient, AIConfig};
+    use codesage_ai::{AIClient, SemanticIndex};
     use codesage_core::AIReviewer;
 
-    let ai_client = AIClient::with_config(AIConfig::default());
-    match ai_client.review(context).await {
+    let ai_client = AIClient::with_config(build_ai_config(config, model_override));
+
+    let result = match index_db {
+        Some(db) => match SemanticIndex::open(&PathBuf::from(db)) {
+            Ok(index) => ai_client.review_with_index(context, &index).await,
+            Err(e) => {
+                eprintln!("{} Could not open semantic index {}: {}", "⚠".yellow(), db, e);
+                ai_client.review(context).await
+            }
+        },
+        None => ai_client.review(context).await,
+    };
+
+    match result {
         Ok(review_result) => {
             println!("\n{} AI Review Complete", "✓".green().bold());
             if !review_result.issues.is_empty() {
@@ -397,6 +829,39 @@ async fn run_ai_review(context: &AnalysisContext) {
     }
 }
 
+/// Resolve `AIConfig` from, in increasing precedence: built-in defaults
+/// (which already read `ANTHROPIC_API_KEY` from the environment), the
+/// project's `codesage.toml`, and finally any `--model` CLI override.
+fn build_ai_config(config: &Config, model_override: Option<String>) -> codesage_ai::AIConfig {
+    let mut ai_config = codesage_ai::AIConfig::default();
+
+    if let Some(model) = &config.ai.model {
+        ai_config.model = model.clone();
+    }
+    if let Some(api_base_url) = &config.ai.api_base_url {
+        ai_config.api_base_url = api_base_url.clone();
+    }
+    if let Some(timeout_seconds) = config.ai.timeout_seconds {
+        ai_config.timeout_seconds = timeout_seconds;
+    }
+    if let Some(context_window_tokens) = config.ai.context_window_tokens {
+        ai_config.context_window_tokens = context_window_tokens;
+    }
+    if let Some(provider) = &config.ai.provider {
+        ai_config.provider = match provider.as_str() {
+            "openai" => codesage_ai::Provider::OpenAi,
+            "openai_compatible" => codesage_ai::Provider::OpenAiCompatible,
+            _ => codesage_ai::Provider::Anthropic,
+        };
+    }
+
+    if let Some(model) = model_override {
+        ai_config.model = model;
+    }
+
+    ai_config
+}
+
 // ============================================================================
 // SARIF Format Support (Static Analysis Results Interchange Format)
 // ============================================================================
@@ -460,6 +925,40 @@ struct SarifResult {
     level: String,
     message: SarifMessage,
     locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fixes: Option<Vec<SarifFix>>,
+    /// Stable identity for this result across runs, so a SARIF consumer can
+    /// diff two reports without relying on line/column. See
+    /// `sarif_fingerprint`.
+    #[serde(rename = "partialFingerprints")]
+    partial_fingerprints: std::collections::HashMap<String, String>,
+    /// Set by `--baseline`: `"unchanged"` if this result's fingerprint was
+    /// present in the baseline report, `"new"` otherwise. Absent when no
+    /// baseline was given.
+    #[serde(rename = "baselineState", skip_serializing_if = "Option::is_none")]
+    baseline_state: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SarifFix {
+    description: SarifMessage,
+    #[serde(rename = "artifactChanges")]
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Serialize)]
+struct SarifArtifactChange {
+    #[serde(rename = "artifactLocation")]
+    artifact_location: SarifArtifactLocation,
+    replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Serialize)]
+struct SarifReplacement {
+    #[serde(rename = "deletedRegion")]
+    deleted_region: SarifRegion,
+    #[serde(rename = "insertedContent")]
+    inserted_content: SarifMessage,
 }
 
 #[derive(Serialize)]
@@ -526,30 +1025,61 @@ fn convert_to_sarif(issues: &[Issue]) -> SarifReport {
     // Convert issues to SARIF results
     let results: Vec<SarifResult> = issues
         .iter()
-        .map(|issue| SarifResult {
-            rule_id: issue.id.clone(),
-            level: severity_to_sarif_level(&issue.severity),
-            message: SarifMessage {
-                text: format!("{}: {}", issue.message, issue.explanation),
-            },
-            locations: vec![SarifLocation {
-                physical_location: SarifPhysicalLocation {
-                    artifact_location: SarifArtifactLocation {
-                        uri: issue
-                            .location
-                            .file_path
-                            .display()
-                            .to_string()
-                            .replace('\\', "/"),
-                    },
-                    region: SarifRegion {
-                        start_line: issue.location.start_line as u32,
-                        start_column: issue.location.start_column as u32,
-                        end_line: issue.location.end_line as u32,
-                        end_column: issue.location.end_column as u32,
-                    },
+        .map(|issue| {
+            let uri = issue
+                .location
+                .file_path
+                .display()
+                .to_string()
+                .replace('\\', "/");
+
+            let mut partial_fingerprints = std::collections::HashMap::new();
+            partial_fingerprints.insert(
+                "primaryLocationLineHash".to_string(),
+                sarif_fingerprint(issue),
+            );
+
+            SarifResult {
+                rule_id: issue.id.clone(),
+                level: severity_to_sarif_level(&issue.severity),
+                message: SarifMessage {
+                    text: format!("{}: {}", issue.message, issue.explanation),
                 },
-            }],
+                locations: vec![SarifLocation {
+                    physical_location: SarifPhysicalLocation {
+                        artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                        region: SarifRegion {
+                            start_line: issue.location.start_line as u32,
+                            start_column: issue.location.start_column as u32,
+                            end_line: issue.location.end_line as u32,
+                            end_column: issue.location.end_column as u32,
+                        },
+                    },
+                }],
+                fixes: issue.fix_suggestion.as_ref().map(|fix| {
+                    vec![SarifFix {
+                        description: SarifMessage {
+                            text: fix.description.clone(),
+                        },
+                        artifact_changes: vec![SarifArtifactChange {
+                            artifact_location: SarifArtifactLocation { uri: uri.clone() },
+                            replacements: vec![SarifReplacement {
+                                deleted_region: SarifRegion {
+                                    start_line: issue.location.start_line as u32,
+                                    start_column: issue.location.start_column as u32,
+                                    end_line: issue.location.end_line as u32,
+                                    end_column: issue.location.end_column as u32,
+                                },
+                                inserted_content: SarifMessage {
+                                    text: fix.diff.clone(),
+                                },
+                            }],
+                        }],
+                    }]
+                }),
+                partial_fingerprints,
+                baseline_state: None,
+            }
         })
         .collect();
 
@@ -579,3 +1109,351 @@ fn severity_to_sarif_level(severity: &codesage_core::Severity) -> String {
         codesage_core::Severity::P3 => "note".to_string(),
     }
 }
+
+/// Stable fingerprint for a SARIF result, hashed over the rule id plus a
+/// normalized snippet of the issue's code region (its own lines plus a
+/// line of surrounding context on each side) rather than line/column, so
+/// an unrelated edit above the issue doesn't change its identity.
+fn sarif_fingerprint(issue: &Issue) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    issue.id.hash(&mut hasher);
+    normalized_issue_snippet(issue).hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// The issue's code region plus one line of context above and below,
+/// collapsed to single-spaced words. Whitespace reformatting or lines
+/// added elsewhere in the file then leave the fingerprint unchanged.
+fn normalized_issue_snippet(issue: &Issue) -> String {
+    let Ok(source) = std::fs::read_to_string(&issue.location.file_path) else {
+        return String::new();
+    };
+    let lines: Vec<&str> = source.lines().collect();
+    let start = issue.location.start_line.saturating_sub(1).max(1);
+    let end = (issue.location.end_line + 1).min(lines.len().max(1));
+
+    let snippet = lines
+        .get(start.saturating_sub(1)..end.min(lines.len()))
+        .unwrap_or_default()
+        .join(" ");
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Load a prior SARIF report's `partialFingerprints` and mark each result
+/// in `sarif` as `"unchanged"` if its fingerprint was already present,
+/// `"new"` otherwise. Returns the number of new results. A baseline that
+/// can't be read or parsed is treated as empty, so everything reports new.
+fn apply_sarif_baseline(sarif: &mut SarifReport, baseline_path: &str) -> usize {
+    let known = load_sarif_fingerprints(baseline_path);
+    let mut new_count = 0;
+
+    for run in &mut sarif.runs {
+        for result in &mut run.results {
+            let is_known = result
+                .partial_fingerprints
+                .get("primaryLocationLineHash")
+                .is_some_and(|hash| known.contains(hash));
+
+            result.baseline_state = Some(if is_known {
+                "unchanged".to_string()
+            } else {
+                new_count += 1;
+                "new".to_string()
+            });
+        }
+    }
+
+    new_count
+}
+
+/// Every `primaryLocationLineHash` fingerprint present in a previously
+/// generated SARIF report.
+fn load_sarif_fingerprints(path: &str) -> std::collections::HashSet<String> {
+    let mut fingerprints = std::collections::HashSet::new();
+
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return fingerprints;
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) else {
+        return fingerprints;
+    };
+
+    let runs = value.get("runs").and_then(|r| r.as_array());
+    for run in runs.into_iter().flatten() {
+        let results = run.get("results").and_then(|r| r.as_array());
+        for result in results.into_iter().flatten() {
+            if let Some(hash) = result
+                .get("partialFingerprints")
+                .and_then(|f| f.get("primaryLocationLineHash"))
+                .and_then(|h| h.as_str())
+            {
+                fingerprints.insert(hash.to_string());
+            }
+        }
+    }
+
+    fingerprints
+}
+
+#[cfg(test)]
+mod sarif_tests {
+    use super::*;
+    use codesage_core::{IssueCategory, Location, Severity};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn issue(id: &str, severity: Severity, start_line: usize) -> Issue {
+        Issue {
+            id: id.to_string(),
+            severity,
+            category: IssueCategory::Maintainability,
+            location: Location {
+                file_path: PathBuf::from("does/not/exist.rs"),
+                start_line,
+                start_column: 1,
+                end_line: start_line,
+                end_column: 1,
+            },
+            message: "example issue".to_string(),
+            explanation: "example explanation".to_string(),
+            fix_suggestion: None,
+            confidence: 1.0,
+            related_locations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_severity_to_sarif_level_mapping() {
+        assert_eq!(severity_to_sarif_level(&Severity::P0), "error");
+        assert_eq!(severity_to_sarif_level(&Severity::P1), "warning");
+        assert_eq!(severity_to_sarif_level(&Severity::P2), "note");
+        assert_eq!(severity_to_sarif_level(&Severity::P3), "note");
+    }
+
+    #[test]
+    fn test_convert_to_sarif_deduplicates_rules() {
+        let issues = vec![
+            issue("BUG001", Severity::P1, 1),
+            issue("BUG001", Severity::P1, 2),
+        ];
+        let report = convert_to_sarif(&issues);
+
+        assert_eq!(report.runs.len(), 1);
+        assert_eq!(report.runs[0].tool.driver.rules.len(), 1);
+        assert_eq!(report.runs[0].results.len(), 2);
+    }
+
+    /// The same issue (same id, at the same source location) always
+    /// fingerprints identically, and a different rule id changes the
+    /// fingerprint.
+    #[test]
+    fn test_sarif_fingerprint_is_stable_for_identical_issue() {
+        let a = sarif_fingerprint(&issue("BUG001", Severity::P1, 5));
+        let b = sarif_fingerprint(&issue("BUG001", Severity::P1, 5));
+        assert_eq!(a, b);
+
+        let different_id = sarif_fingerprint(&issue("BUG002", Severity::P1, 5));
+        assert_ne!(a, different_id);
+    }
+
+    /// `apply_sarif_baseline` marks a result "unchanged" only if its
+    /// fingerprint was present in the baseline report, and "new" otherwise.
+    #[test]
+    fn test_apply_sarif_baseline_marks_known_and_new_results() {
+        let known_issue = issue("BUG001", Severity::P1, 1);
+        let new_issue = issue("BUG002", Severity::P1, 2);
+        let known_fingerprint = sarif_fingerprint(&known_issue);
+
+        let mut baseline_file = NamedTempFile::with_suffix(".sarif.json").unwrap();
+        let baseline_json = serde_json::json!({
+            "runs": [{
+                "results": [{
+                    "partialFingerprints": { "primaryLocationLineHash": known_fingerprint }
+                }]
+            }]
+        });
+        write!(baseline_file, "{}", baseline_json).unwrap();
+
+        let mut sarif = convert_to_sarif(&[known_issue, new_issue]);
+        let new_count = apply_sarif_baseline(&mut sarif, baseline_file.path().to_str().unwrap());
+
+        assert_eq!(new_count, 1);
+        let states: Vec<_> = sarif.runs[0]
+            .results
+            .iter()
+            .map(|r| r.baseline_state.clone().unwrap())
+            .collect();
+        assert_eq!(states.iter().filter(|s| *s == "unchanged").count(), 1);
+        assert_eq!(states.iter().filter(|s| *s == "new").count(), 1);
+    }
+
+    /// A baseline path that can't be read (missing file) is treated as an
+    /// empty baseline, so every result reports "new".
+    #[test]
+    fn test_apply_sarif_baseline_missing_file_treats_everything_as_new() {
+        let mut sarif = convert_to_sarif(&[issue("BUG001", Severity::P1, 1)]);
+        let new_count = apply_sarif_baseline(&mut sarif, "/does/not/exist/baseline.json");
+
+        assert_eq!(new_count, 1);
+        assert_eq!(sarif.runs[0].results[0].baseline_state.as_deref(), Some("new"));
+    }
+}
+
+/// A single entry of a GitLab Code Quality report, as consumed by merge
+/// request widgets: https://docs.gitlab.com/ee/ci/testing/code_quality.html
+#[derive(Debug, Serialize)]
+struct GitlabCodeQualityIssue {
+    description: String,
+    check_name: String,
+    fingerprint: String,
+    severity: String,
+    location: GitlabLocation,
+}
+
+#[derive(Debug, Serialize)]
+struct GitlabLocation {
+    path: String,
+    lines: GitlabLines,
+}
+
+#[derive(Debug, Serialize)]
+struct GitlabLines {
+    begin: usize,
+    end: usize,
+}
+
+/// Convert CodeSage issues to a GitLab Code Quality report. GitLab diffs
+/// this against the target branch's report using `fingerprint` to decide
+/// which issues are "new" vs. already present, so the fingerprint has to
+/// stay stable across commits as long as the issue itself hasn't moved.
+fn convert_to_gitlab_code_quality(issues: &[Issue]) -> Vec<GitlabCodeQualityIssue> {
+    issues
+        .iter()
+        .map(|issue| {
+            let path = issue
+                .location
+                .file_path
+                .display()
+                .to_string()
+                .replace('\\', "/");
+
+            GitlabCodeQualityIssue {
+                description: issue.message.clone(),
+                check_name: issue.id.clone(),
+                fingerprint: gitlab_fingerprint(&path, &issue.id, &issue.message),
+                severity: severity_to_gitlab_severity(&issue.severity),
+                location: GitlabLocation {
+                    path,
+                    lines: GitlabLines {
+                        begin: issue.location.start_line,
+                        end: issue.location.end_line,
+                    },
+                },
+            }
+        })
+        .collect()
+}
+
+/// Convert CodeSage severity to a GitLab Code Quality severity: `P0`..`P3`
+/// map one-to-one onto `blocker`/`major`/`minor`/`info`. GitLab's
+/// `critical` level is not used.
+fn severity_to_gitlab_severity(severity: &codesage_core::Severity) -> String {
+    match severity {
+        codesage_core::Severity::P0 => "blocker".to_string(),
+        codesage_core::Severity::P1 => "major".to_string(),
+        codesage_core::Severity::P2 => "minor".to_string(),
+        codesage_core::Severity::P3 => "info".to_string(),
+    }
+}
+
+/// Stable fingerprint identifying an issue across commits, hashed over the
+/// file path, rule id and message rather than line numbers so the same
+/// issue keeps its identity even when surrounding code shifts it around.
+fn gitlab_fingerprint(path: &str, rule_id: &str, message: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    rule_id.hash(&mut hasher);
+    message.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod gitlab_code_quality_tests {
+    use super::*;
+    use codesage_core::{IssueCategory, Location, Severity};
+
+    fn issue(id: &str, severity: Severity, path: &str, start_line: usize) -> Issue {
+        Issue {
+            id: id.to_string(),
+            severity,
+            category: IssueCategory::Maintainability,
+            location: Location {
+                file_path: PathBuf::from(path),
+                start_line,
+                start_column: 1,
+                end_line: start_line,
+                end_column: 1,
+            },
+            message: "example issue".to_string(),
+            explanation: "example explanation".to_string(),
+            fix_suggestion: None,
+            confidence: 1.0,
+            related_locations: Vec::new(),
+        }
+    }
+
+    /// Each severity maps onto its own GitLab level, in order, as
+    /// documented on `severity_to_gitlab_severity`.
+    #[test]
+    fn test_severity_to_gitlab_severity_mapping() {
+        assert_eq!(severity_to_gitlab_severity(&Severity::P0), "blocker");
+        assert_eq!(severity_to_gitlab_severity(&Severity::P1), "major");
+        assert_eq!(severity_to_gitlab_severity(&Severity::P2), "minor");
+        assert_eq!(severity_to_gitlab_severity(&Severity::P3), "info");
+    }
+
+    #[test]
+    fn test_convert_to_gitlab_code_quality_maps_fields() {
+        let issues = vec![issue("BUG001", Severity::P1, "src/main.rs", 10)];
+        let report = convert_to_gitlab_code_quality(&issues);
+
+        assert_eq!(report.len(), 1);
+        let entry = &report[0];
+        assert_eq!(entry.check_name, "BUG001");
+        assert_eq!(entry.severity, "major");
+        assert_eq!(entry.location.path, "src/main.rs");
+        assert_eq!(entry.location.lines.begin, 10);
+        assert_eq!(entry.location.lines.end, 10);
+    }
+
+    /// Windows-style separators get normalized to `/` so the path matches
+    /// what GitLab expects to diff against the repo tree.
+    #[test]
+    fn test_convert_to_gitlab_code_quality_normalizes_windows_paths() {
+        let issues = vec![issue("BUG001", Severity::P1, "src\\windows.rs", 1)];
+        let report = convert_to_gitlab_code_quality(&issues);
+        assert_eq!(report[0].location.path, "src/windows.rs");
+    }
+
+    /// The fingerprint is used by GitLab to diff issues against the target
+    /// branch's report, so it must stay stable for identical issues and
+    /// change when the rule id or message does.
+    #[test]
+    fn test_gitlab_fingerprint_is_stable_and_sensitive_to_content() {
+        let a = gitlab_fingerprint("src/main.rs", "BUG001", "example issue");
+        let b = gitlab_fingerprint("src/main.rs", "BUG001", "example issue");
+        assert_eq!(a, b);
+
+        let different_rule = gitlab_fingerprint("src/main.rs", "BUG002", "example issue");
+        assert_ne!(a, different_rule);
+
+        let different_message = gitlab_fingerprint("src/main.rs", "BUG001", "different");
+        assert_ne!(a, different_message);
+    }
+}
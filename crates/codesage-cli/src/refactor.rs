@@ -0,0 +1,185 @@
+//! The `Refactor --pattern/--replace` mode: run a single Structured Search
+//! and Replace rule (see `codesage_refactor::ssr`) against a file and write
+//! back whichever matches are accepted.
+//!
+//! Matches are collected as `Suggestion`s carrying a `fix_suggestion.diff`
+//! in the same unified-diff shape `crate::diff` already parses, so applying
+//! them reuses that engine: every accepted match's hunks are parsed, merged
+//! into one list and applied in a single `diff::apply_hunks` call (which
+//! applies bottom-to-top, so earlier matches' line numbers stay valid).
+//! `--interactive` prompts for each match before it's included; otherwise
+//! every match found is applied.
+
+use crate::diff;
+use codesage_core::{AnalysisContext, Result};
+use codesage_parser::CodeParser;
+use codesage_refactor::extract_method;
+use codesage_refactor::ssr::{apply_rules, SsrRule};
+use colored::Colorize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+pub fn handle_refactor(path: String, interactive: bool, pattern: String, replace: String) -> Result<()> {
+    println!("{} Refactoring: {}", "♻️".green(), path.bold());
+    println!("   Pattern: {} ==>> {}", pattern, replace);
+
+    let rule_text = format!("{} ==>> {}", pattern, replace);
+    let rule = match SsrRule::parse(&rule_text, None) {
+        Ok(rule) => rule,
+        Err(e) => {
+            eprintln!("{} Invalid SSR rule: {}", "✗".red(), e);
+            return Ok(());
+        }
+    };
+
+    let path_buf = PathBuf::from(&path);
+    let mut parser = CodeParser::new();
+    let Ok(parsed) = parser.parse_file(&path_buf) else {
+        eprintln!("{} Could not parse {}", "✗".red(), path);
+        return Ok(());
+    };
+    let context = AnalysisContext {
+        file_path: path.clone().into(),
+        source_code: parsed.source().to_string(),
+        language: parsed.language,
+    };
+
+    let suggestions = apply_rules(&context, std::slice::from_ref(&rule));
+    if suggestions.is_empty() {
+        println!("\n{} No matches found!", "✓".green().bold());
+        return Ok(());
+    }
+
+    let mut hunks = Vec::new();
+    let mut applied = 0;
+    let mut skipped = 0;
+    for suggestion in &suggestions {
+        let Some(fix) = &suggestion.fix_suggestion else {
+            continue;
+        };
+
+        let should_apply = if interactive {
+            prompt_to_apply(suggestion)
+        } else {
+            true
+        };
+
+        if !should_apply {
+            skipped += 1;
+            continue;
+        }
+
+        match diff::parse(&fix.diff) {
+            Ok(parsed_hunks) => {
+                hunks.extend(parsed_hunks);
+                applied += 1;
+            }
+            Err(e) => {
+                eprintln!("{} Could not parse SSR diff: {}", "✗".red(), e);
+                skipped += 1;
+            }
+        }
+    }
+
+    if hunks.is_empty() {
+        println!("\n{} No matches applied.", "⚠".yellow().bold());
+        return Ok(());
+    }
+
+    let Some(patched) = diff::apply_hunks(&context.source_code, &hunks) else {
+        eprintln!(
+            "{} Matches overlapped or did not apply cleanly; no changes written",
+            "✗".red()
+        );
+        return Ok(());
+    };
+
+    std::fs::write(&path, patched)?;
+
+    println!("\n{}", "Refactor summary:".bold().underline());
+    println!("  Applied: {}", applied.to_string().green());
+    println!("  Skipped: {}", skipped.to_string().yellow());
+
+    Ok(())
+}
+
+/// Run Extract Method against `path:range` (a `START:END` line range) and
+/// write the result if it applies cleanly. `range`'s both ends are
+/// 1-indexed and inclusive.
+pub fn handle_extract_method(path: String, range: String, name: Option<String>) -> Result<()> {
+    println!("{} Refactoring: {}", "♻️".green(), path.bold());
+    println!("   Extract: lines {}", range);
+
+    let Some((start, end)) = range.split_once(':') else {
+        eprintln!("{} --extract must be a line range like START:END", "✗".red());
+        return Ok(());
+    };
+    let (Ok(start_line), Ok(end_line)) = (start.trim().parse::<usize>(), end.trim().parse::<usize>()) else {
+        eprintln!("{} --extract must be a line range like START:END", "✗".red());
+        return Ok(());
+    };
+
+    let path_buf = PathBuf::from(&path);
+    let mut parser = CodeParser::new();
+    let Ok(parsed) = parser.parse_file(&path_buf) else {
+        eprintln!("{} Could not parse {}", "✗".red(), path);
+        return Ok(());
+    };
+    let context = AnalysisContext {
+        file_path: path.clone().into(),
+        source_code: parsed.source().to_string(),
+        language: parsed.language,
+    };
+
+    let suggestion = match extract_method::extract_method(&context, start_line, end_line, name.as_deref()) {
+        Ok(suggestion) => suggestion,
+        Err(reason) => {
+            eprintln!("{} Could not extract method: {}", "✗".red(), reason);
+            return Ok(());
+        }
+    };
+
+    println!("\n{} {}", "?".cyan().bold(), suggestion.description);
+    let Some(fix) = &suggestion.fix_suggestion else {
+        eprintln!("{} Extraction produced no fix to apply", "✗".red());
+        return Ok(());
+    };
+    println!("{}", fix.diff);
+
+    let hunks = match diff::parse(&fix.diff) {
+        Ok(hunks) => hunks,
+        Err(e) => {
+            eprintln!("{} Could not parse extraction diff: {}", "✗".red(), e);
+            return Ok(());
+        }
+    };
+
+    let Some(patched) = diff::apply_hunks(&context.source_code, &hunks) else {
+        eprintln!(
+            "{} Extraction did not apply cleanly; no changes written",
+            "✗".red()
+        );
+        return Ok(());
+    };
+
+    std::fs::write(&path, patched)?;
+    println!("\n{} Extraction applied.", "✓".green().bold());
+
+    Ok(())
+}
+
+/// Show a matched suggestion's diff and ask the user whether to apply it.
+fn prompt_to_apply(suggestion: &codesage_core::Suggestion) -> bool {
+    println!("\n{} {}", "?".cyan().bold(), suggestion.description);
+    if let Some(fix) = &suggestion.fix_suggestion {
+        println!("{}", fix.diff);
+    }
+    print!("Apply this match? [y/N] ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -0,0 +1,285 @@
+//! Suppress known-false-positive issues before they reach any output
+//! format (text, json, sarif, gitlab).
+//!
+//! Borrows the ignore-regex-list approach from CASR's stack-frame/filepath
+//! filters: a `.codesageignore` file and the `[ignore]` config table each
+//! hold regexes matched against an issue's file path and rule id, and an
+//! inline `// codesage:ignore <rule-id>` comment on the issue's own source
+//! line suppresses it directly. All three converge on `filter`, called
+//! once after `engine.analyze` and before display, so every format sees
+//! the same suppressed set.
+
+use codesage_core::Issue;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+const IGNORE_FILE_NAME: &str = ".codesageignore";
+const INLINE_DIRECTIVE: &str = "codesage:ignore";
+
+/// Compiled suppression rules, loaded once per run via `IgnoreRules::load`.
+#[derive(Default)]
+pub struct IgnoreRules {
+    path_patterns: Vec<Regex>,
+    rule_patterns: Vec<Regex>,
+}
+
+impl IgnoreRules {
+    /// Search upward from `start` for `.codesageignore` (one regex per
+    /// non-empty, non-`#`-comment line, matched against both the file path
+    /// and the rule id) and merge it with the `[ignore]` config table's
+    /// `paths`/`rules` lists.
+    pub fn load(start: &Path, config: &crate::config::IgnoreConfigSection) -> Self {
+        let mut path_patterns = Vec::new();
+        let mut rule_patterns = Vec::new();
+
+        if let Some(ignore_file) = find_ignore_file(start)
+            && let Ok(text) = std::fs::read_to_string(&ignore_file)
+        {
+            for line in text.lines() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                if let Some(re) = compile(line, &ignore_file.display().to_string()) {
+                    // A .codesageignore line has no table to say whether it
+                    // means a path or a rule id, so it's tried against both.
+                    path_patterns.push(re.clone());
+                    rule_patterns.push(re);
+                }
+            }
+        }
+
+        for pattern in &config.paths {
+            if let Some(re) = compile(pattern, "codesage.toml [ignore] paths") {
+                path_patterns.push(re);
+            }
+        }
+        for pattern in &config.rules {
+            if let Some(re) = compile(pattern, "codesage.toml [ignore] rules") {
+                rule_patterns.push(re);
+            }
+        }
+
+        IgnoreRules {
+            path_patterns,
+            rule_patterns,
+        }
+    }
+
+    /// Whether `issue` should be dropped: its file path matches a path
+    /// pattern, its id matches a rule pattern, or its own source line
+    /// carries an inline `codesage:ignore` directive.
+    fn suppresses(&self, issue: &Issue) -> bool {
+        let path = issue.location.file_path.display().to_string();
+        if self.path_patterns.iter().any(|re| re.is_match(&path)) {
+            return true;
+        }
+        if self.rule_patterns.iter().any(|re| re.is_match(&issue.id)) {
+            return true;
+        }
+        inline_directive_matches(issue)
+    }
+}
+
+fn compile(pattern: &str, source: &str) -> Option<Regex> {
+    match Regex::new(pattern) {
+        Ok(re) => Some(re),
+        Err(e) => {
+            eprintln!(
+                "Warning: invalid ignore regex `{}` in {}: {}",
+                pattern, source, e
+            );
+            None
+        }
+    }
+}
+
+fn find_ignore_file(start: &Path) -> Option<PathBuf> {
+    let mut dir = if start.is_dir() {
+        Some(start.to_path_buf())
+    } else {
+        start.parent().map(Path::to_path_buf)
+    };
+
+    while let Some(current) = dir {
+        let candidate = current.join(IGNORE_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        dir = current.parent().map(Path::to_path_buf);
+    }
+
+    None
+}
+
+/// Whether the source line at `issue.location.start_line` carries a
+/// `// codesage:ignore` directive, either bare (suppresses any rule on
+/// that line) or naming this issue's rule id specifically.
+fn inline_directive_matches(issue: &Issue) -> bool {
+    let Ok(source) = std::fs::read_to_string(&issue.location.file_path) else {
+        return false;
+    };
+    let Some(line) = source
+        .lines()
+        .nth(issue.location.start_line.saturating_sub(1))
+    else {
+        return false;
+    };
+    let Some(idx) = line.find(INLINE_DIRECTIVE) else {
+        return false;
+    };
+
+    let rest = line[idx + INLINE_DIRECTIVE.len()..].trim();
+    rest.is_empty() || rest == issue.id
+}
+
+/// Drop every issue `rules` suppresses, returning the survivors and how
+/// many were dropped.
+pub fn filter(issues: Vec<Issue>, rules: &IgnoreRules) -> (Vec<Issue>, usize) {
+    let total = issues.len();
+    let kept: Vec<Issue> = issues
+        .into_iter()
+        .filter(|issue| !rules.suppresses(issue))
+        .collect();
+    let suppressed = total - kept.len();
+    (kept, suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IgnoreConfigSection;
+    use codesage_core::{IssueCategory, Location, Severity};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn issue(id: &str, file_path: &Path, start_line: usize) -> Issue {
+        Issue {
+            id: id.to_string(),
+            severity: Severity::P2,
+            category: IssueCategory::Maintainability,
+            location: Location {
+                file_path: file_path.to_path_buf(),
+                start_line,
+                start_column: 1,
+                end_line: start_line,
+                end_column: 1,
+            },
+            message: "example issue".to_string(),
+            explanation: "example explanation".to_string(),
+            fix_suggestion: None,
+            confidence: 1.0,
+            related_locations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_config_path_pattern_suppresses_matching_file() {
+        let config = IgnoreConfigSection {
+            paths: vec!["generated/.*".to_string()],
+            rules: vec![],
+        };
+        let rules = IgnoreRules::load(Path::new("/nonexistent"), &config);
+
+        let issues = vec![
+            issue("BUG001", Path::new("generated/foo.rs"), 1),
+            issue("BUG002", Path::new("src/foo.rs"), 1),
+        ];
+        let (kept, suppressed) = filter(issues, &rules);
+
+        assert_eq!(suppressed, 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].id, "BUG002");
+    }
+
+    #[test]
+    fn test_config_rule_pattern_suppresses_matching_id() {
+        let config = IgnoreConfigSection {
+            paths: vec![],
+            rules: vec!["^STYLE".to_string()],
+        };
+        let rules = IgnoreRules::load(Path::new("/nonexistent"), &config);
+
+        let issues = vec![
+            issue("STYLE001", Path::new("src/foo.rs"), 1),
+            issue("BUG001", Path::new("src/foo.rs"), 1),
+        ];
+        let (kept, suppressed) = filter(issues, &rules);
+
+        assert_eq!(suppressed, 1);
+        assert_eq!(kept[0].id, "BUG001");
+    }
+
+    /// An invalid regex is skipped with a warning rather than panicking or
+    /// poisoning the rest of the list.
+    #[test]
+    fn test_invalid_config_pattern_is_skipped() {
+        let config = IgnoreConfigSection {
+            paths: vec!["(unclosed".to_string()],
+            rules: vec![],
+        };
+        let rules = IgnoreRules::load(Path::new("/nonexistent"), &config);
+
+        let issues = vec![issue("BUG001", Path::new("src/foo.rs"), 1)];
+        let (kept, suppressed) = filter(issues, &rules);
+
+        assert_eq!(suppressed, 0);
+        assert_eq!(kept.len(), 1);
+    }
+
+    /// A bare `// codesage:ignore` suppresses any rule on that line; one
+    /// naming a specific rule id only suppresses that rule.
+    #[test]
+    fn test_inline_directive_suppresses_matching_line() {
+        let mut file = NamedTempFile::with_suffix(".rs").unwrap();
+        writeln!(file, "fn main() {{").unwrap();
+        writeln!(file, "    let x = 1; // codesage:ignore BUG001").unwrap();
+        writeln!(file, "    let y = 2; // codesage:ignore").unwrap();
+        writeln!(file, "    let z = 3;").unwrap();
+        writeln!(file, "}}").unwrap();
+
+        let config = IgnoreConfigSection::default();
+        let rules = IgnoreRules::load(Path::new("/nonexistent"), &config);
+
+        let issues = vec![
+            issue("BUG001", file.path(), 2),
+            issue("BUG002", file.path(), 2),
+            issue("BUG003", file.path(), 3),
+            issue("BUG004", file.path(), 4),
+        ];
+        let (kept, suppressed) = filter(issues, &rules);
+
+        // Line 2 suppresses only BUG001 (named); line 3's bare directive
+        // suppresses BUG003; line 4 has no directive at all.
+        assert_eq!(suppressed, 2);
+        let kept_ids: Vec<&str> = kept.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(kept_ids, vec!["BUG002", "BUG004"]);
+    }
+
+    /// `.codesageignore` lines apply to both paths and rule ids, since the
+    /// file format has no way to say which one a line means.
+    #[test]
+    fn test_codesageignore_file_suppresses_path_and_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".codesageignore"),
+            "BUG001\nvendor/\n# a comment\n\n",
+        )
+        .unwrap();
+
+        let config = IgnoreConfigSection::default();
+        let rules = IgnoreRules::load(dir.path(), &config);
+
+        let issues = vec![
+            issue("BUG001", &dir.path().join("src/foo.rs"), 1),
+            issue("BUG002", &dir.path().join("src/foo.rs"), 1),
+            issue("BUG003", &dir.path().join("vendor/lib.rs"), 1),
+        ];
+        let (kept, suppressed) = filter(issues, &rules);
+
+        // BUG001 goes by rule id, BUG003 by its path under vendor/.
+        assert_eq!(suppressed, 2);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].id, "BUG002");
+    }
+}
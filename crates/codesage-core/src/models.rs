@@ -14,6 +14,11 @@ pub struct Issue {
     pub explanation: String,
     pub fix_suggestion: Option<Fix>,
     pub confidence: f32,
+    /// Other locations this issue also applies to, e.g. the sibling
+    /// instances of a duplicated code block. Empty for issues that only
+    /// ever point at a single place.
+    #[serde(default)]
+    pub related_locations: Vec<Location>,
 }
 
 /// Severity level of an issue
@@ -65,6 +70,16 @@ pub struct CodeMetrics {
     pub test_coverage: Option<f32>,
     pub duplication_percentage: f32,
     pub technical_debt_minutes: u32,
+    /// RuboCop-style ABC (Assignment/Branch/Condition) magnitude:
+    /// `sqrt(A^2 + B^2 + C^2)` of the file's most ABC-heavy function.
+    pub abc_size: f32,
+    /// Halstead volume `V = N * log2(n)`, the real figure fed into
+    /// `maintainability_index` (previously approximated).
+    pub halstead_volume: f32,
+    /// Halstead difficulty `D = (n1/2) * (N2/n2)`.
+    pub halstead_difficulty: f32,
+    /// Halstead effort `E = D * V`.
+    pub halstead_effort: f32,
 }
 
 /// Result of code review
@@ -75,6 +90,9 @@ pub struct CodeReviewResult {
     pub metrics: CodeMetrics,
     pub suggestions: Vec<Suggestion>,
     pub timestamp: String,
+    /// Number of tokens the review prompt consumed, so users can see how
+    /// large (and whether chunked) a review was.
+    pub prompt_tokens: usize,
 }
 
 /// Refactoring suggestion
@@ -86,6 +104,11 @@ pub struct Suggestion {
     pub before_code: String,
     pub after_code: String,
     pub impact: Impact,
+    /// A ready-to-apply patch for this suggestion, when one can be
+    /// generated mechanically (e.g. an SSR rewrite). `None` for
+    /// suggestions that only describe a change in prose.
+    #[serde(default)]
+    pub fix_suggestion: Option<Fix>,
 }
 
 /// Type of refactoring
@@ -98,6 +121,9 @@ pub enum RefactoringType {
     SimplifyConditional,
     RemoveDeadCode,
     IntroduceDesignPattern,
+    /// A rewrite produced by a Structured Search and Replace rule, e.g.
+    /// `foo($a, $b) ==>> bar($b, $a)`. See `codesage_refactor::ssr`.
+    PatternRewrite,
 }
 
 /// Impact of a change
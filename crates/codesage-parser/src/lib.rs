@@ -0,0 +1,9 @@
+//! CodeSage Parser Library
+//!
+//! Tree-sitter-backed parsing of source files into `ParsedCode`.
+
+mod parser;
+mod registry;
+
+pub use parser::{CodeParser, ParseDiagnostic, ParsedCode};
+pub use registry::grammar_for;
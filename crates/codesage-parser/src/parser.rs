@@ -1,8 +1,9 @@
 //! Code parser implementation
 
+use crate::registry;
 use codesage_core::{CodeSageError, Language, Result};
 use std::path::Path;
-use tree_sitter::{Parser, Tree};
+use tree_sitter::{InputEdit, Node, Parser, Point, Tree};
 
 /// Main code parser
 pub struct CodeParser {
@@ -18,7 +19,7 @@ impl CodeParser {
     }
 
     /// Parse a file
-    pub fn parse_file(&self, path: &Path) -> Result<ParsedCode> {
+    pub fn parse_file(&mut self, path: &Path) -> Result<ParsedCode> {
         let extension = path
             .extension()
             .and_then(|e| e.to_str())
@@ -33,34 +34,70 @@ impl CodeParser {
         self.parse_source(&source, language)
     }
 
-    /// Parse source code string
-    pub fn parse_source(&self, source: &str, language: Language) -> Result<ParsedCode> {
-        // Note: Full tree-sitter language support requires language-specific parsers
-        // For now, we store the source and provide basic structure
+    /// Parse source code string, producing a real tree-sitter `Tree` when
+    /// `language`'s grammar is registered (see `crate::registry`). A
+    /// language without a compiled-in grammar still gets a `ParsedCode`
+    /// back with `tree: None`, so callers that only need the raw source
+    /// keep working either way.
+    pub fn parse_source(&mut self, source: &str, language: Language) -> Result<ParsedCode> {
+        let tree = match registry::grammar_for(language) {
+            Some(grammar) => {
+                self.parser.set_language(&grammar).map_err(|e| {
+                    CodeSageError::ParseError(format!(
+                        "failed to load {:?} grammar: {}",
+                        language, e
+                    ))
+                })?;
+                self.parser.parse(source, None)
+            }
+            None => None,
+        };
 
         Ok(ParsedCode {
             language,
             source: source.to_string(),
-            tree: None, // Will be populated when language parser is set
+            tree,
         })
     }
 
-    /// Set the language for the parser
+    /// Load `language`'s tree-sitter grammar into this parser, so a
+    /// subsequent `parse_source` call for that language produces a real
+    /// `Tree` instead of `None`.
     pub fn set_language(&mut self, language: Language) -> Result<()> {
-        // This would be implemented with actual tree-sitter language parsers
-        // For now, we'll leave it as a placeholder
-        match language {
-            Language::Rust => {
-                // parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
-                Err(CodeSageError::ParseError(
-                    "Rust parser not yet initialized".to_string(),
-                ))
+        let grammar = registry::grammar_for(language).ok_or_else(|| {
+            CodeSageError::UnsupportedLanguage(format!("{:?}", language))
+        })?;
+
+        self.parser.set_language(&grammar).map_err(|e| {
+            CodeSageError::ParseError(format!("failed to load {:?} grammar: {}", language, e))
+        })
+    }
+
+    /// Incrementally reparse `prev`, whose tree has already been shifted
+    /// for the pending edit via `ParsedCode::apply_edit`. Passes that
+    /// edited tree to `parser.parse` as the previous tree so tree-sitter
+    /// only re-walks the subtrees the edit actually touched, instead of
+    /// rebuilding the whole file - the path an editor/`--watch` mode should
+    /// use after a small change, rather than calling `parse_source` again.
+    pub fn reparse(&mut self, prev: &ParsedCode) -> Result<ParsedCode> {
+        let tree = match registry::grammar_for(prev.language) {
+            Some(grammar) => {
+                self.parser.set_language(&grammar).map_err(|e| {
+                    CodeSageError::ParseError(format!(
+                        "failed to load {:?} grammar: {}",
+                        prev.language, e
+                    ))
+                })?;
+                self.parser.parse(&prev.source, prev.tree.as_ref())
             }
-            _ => Err(CodeSageError::UnsupportedLanguage(format!(
-                "{:?}",
-                language
-            ))),
-        }
+            None => None,
+        };
+
+        Ok(ParsedCode {
+            language: prev.language,
+            source: prev.source.clone(),
+            tree,
+        })
     }
 }
 
@@ -93,4 +130,122 @@ impl ParsedCode {
     pub fn source(&self) -> &str {
         &self.source
     }
+
+    /// The tree's root node, when a grammar was available to parse it
+    /// (see `tree`).
+    pub fn root_node(&self) -> Option<Node<'_>> {
+        self.tree.as_ref().map(|tree| tree.root_node())
+    }
+
+    /// Whether the tree contains any `ERROR`/`MISSING` node, i.e. the
+    /// grammar couldn't make full sense of the source. Returns `false`
+    /// when there's no tree at all, since "no grammar was available to
+    /// check" isn't the same claim as "checked, and it's clean".
+    pub fn has_errors(&self) -> bool {
+        self.root_node().is_some_and(|root| node_has_errors(root))
+    }
+
+    /// Every `ERROR`/`MISSING` node tree-sitter's error recovery left in
+    /// the tree, each carrying a byte range, line/column span, and a human
+    /// message. Collecting them leaves this `ParsedCode` usable, so
+    /// callers such as `codesage_analyzer::SyntaxAnalyzer` can keep
+    /// analyzing a file that doesn't fully parse instead of aborting on the
+    /// first syntax error.
+    pub fn diagnostics(&self) -> Vec<ParseDiagnostic> {
+        let mut diagnostics = Vec::new();
+        if let Some(root) = self.root_node() {
+            collect_diagnostics(root, &mut diagnostics);
+        }
+        diagnostics
+    }
+
+    /// Record a pending edit to this file's source, shifting the stored
+    /// tree's byte/point ranges to match where they'll fall in
+    /// `new_source` and updating `source` to it. Call this before
+    /// `CodeParser::reparse` so tree-sitter can diff against the edited
+    /// tree instead of reparsing from scratch - the same two-step protocol
+    /// (`Tree::edit` then `Parser::parse` with the old tree) an LSP server
+    /// uses to keep up with keystrokes in a large file.
+    pub fn apply_edit(
+        &mut self,
+        start_byte: usize,
+        old_end_byte: usize,
+        new_end_byte: usize,
+        new_source: &str,
+    ) {
+        if let Some(tree) = &mut self.tree {
+            tree.edit(&InputEdit {
+                start_byte,
+                old_end_byte,
+                new_end_byte,
+                start_position: byte_to_point(&self.source, start_byte),
+                old_end_position: byte_to_point(&self.source, old_end_byte),
+                new_end_position: byte_to_point(new_source, new_end_byte),
+            });
+        }
+
+        self.source = new_source.to_string();
+    }
+}
+
+/// The `Point` (row/column, both 0-based, in bytes) of `byte_offset` within
+/// `text`, clamped to `text`'s length.
+fn byte_to_point(text: &str, byte_offset: usize) -> Point {
+    let offset = byte_offset.min(text.len());
+    let before = &text[..offset];
+    let row = before.bytes().filter(|&b| b == b'\n').count();
+    let column = match before.rfind('\n') {
+        Some(last_newline) => offset - last_newline - 1,
+        None => offset,
+    };
+    Point::new(row, column)
+}
+
+fn node_has_errors(node: Node) -> bool {
+    if node.is_error() || node.is_missing() {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(node_has_errors)
+}
+
+/// A single `ERROR`/`MISSING` node from tree-sitter's error-recovery pass,
+/// with enough span and message detail to report as an `Issue` or an
+/// editor diagnostic without needing the `Tree`/`Node` it came from.
+#[derive(Debug, Clone)]
+pub struct ParseDiagnostic {
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+}
+
+fn collect_diagnostics(node: Node, out: &mut Vec<ParseDiagnostic>) {
+    if node.is_missing() {
+        out.push(diagnostic_for(node, format!("missing `{}`", node.kind())));
+    } else if node.is_error() {
+        out.push(diagnostic_for(node, "unexpected token".to_string()));
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_diagnostics(child, out);
+    }
+}
+
+fn diagnostic_for(node: Node, message: String) -> ParseDiagnostic {
+    let start = node.start_position();
+    let end = node.end_position();
+    ParseDiagnostic {
+        start_byte: node.start_byte(),
+        end_byte: node.end_byte(),
+        start_line: start.row + 1,
+        start_column: start.column + 1,
+        end_line: end.row + 1,
+        end_column: end.column + 1,
+        message,
+    }
 }
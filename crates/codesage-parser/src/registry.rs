@@ -0,0 +1,37 @@
+//! Registry mapping a `Language` to the tree-sitter grammar that parses it.
+//!
+//! Mirrors the grammar-registration pattern most multi-language editors
+//! use: grammars are loaded by language id through one table, so adding a
+//! language is a single entry here rather than a new match arm scattered
+//! across `CodeParser`. A grammar crate is opt-in per `lang-*` Cargo
+//! feature, so a language whose grammar isn't compiled into this build is
+//! simply absent from the registry instead of a compile error.
+
+use codesage_core::Language;
+
+/// The tree-sitter grammar for `language`, or `None` if support for it
+/// wasn't compiled into this build. Exposed beyond this crate so other
+/// consumers that need the raw `tree_sitter::Language` (e.g. to compile
+/// their own `tree_sitter::Query`) don't have to duplicate this table.
+pub fn grammar_for(language: Language) -> Option<tree_sitter::Language> {
+    match language {
+        #[cfg(feature = "lang-rust")]
+        Language::Rust => Some(tree_sitter_rust::LANGUAGE.into()),
+        #[cfg(feature = "lang-javascript")]
+        Language::JavaScript => Some(tree_sitter_javascript::LANGUAGE.into()),
+        #[cfg(feature = "lang-typescript")]
+        Language::TypeScript => Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+        #[cfg(feature = "lang-python")]
+        Language::Python => Some(tree_sitter_python::LANGUAGE.into()),
+        #[cfg(feature = "lang-go")]
+        Language::Go => Some(tree_sitter_go::LANGUAGE.into()),
+        #[cfg(feature = "lang-java")]
+        Language::Java => Some(tree_sitter_java::LANGUAGE.into()),
+        #[cfg(feature = "lang-cpp")]
+        Language::CPP => Some(tree_sitter_cpp::LANGUAGE.into()),
+        #[cfg(feature = "lang-csharp")]
+        Language::CSharp => Some(tree_sitter_c_sharp::LANGUAGE.into()),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
@@ -0,0 +1,519 @@
+//! Extract Method assist: pull a line range out of a function into a new
+//! function, threading the right values through as parameters and return
+//! values.
+//!
+//! Like `ssr`, this hasn't been migrated to walk `CodeParser`'s tree-sitter
+//! `Tree` (see `codesage_parser::ParsedCode::tree`) yet, so it works over
+//! tokenized source text rather than a real AST. The enclosing function is
+//! found by scanning outward from the selected range for the nearest `fn` whose
+//! brace-balanced body contains it, and parameters/return values are
+//! inferred by a textual scan for `let`-bindings and identifier tokens
+//! rather than true data-flow analysis. Generated parameter and return
+//! types are left as bare generics (`T1`, `R1`, ...) since there's no type
+//! inference to draw real types from; the suggestion is never safe to
+//! auto-apply, the same as an SSR rewrite.
+
+use crate::masking::mask_strings_and_comments;
+use codesage_core::{AnalysisContext, Fix, Impact, RefactoringType, Suggestion};
+use std::collections::HashSet;
+
+/// Extract lines `start_line..=end_line` (1-indexed, inclusive) of
+/// `context.source_code` into a new function named `name` (or
+/// `"extracted"`), returning a `Suggestion` whose `fix_suggestion.diff`
+/// replaces the range with a call and inserts the new function after the
+/// enclosing one. Fails with a human-readable reason rather than emitting
+/// broken code when the range can't be extracted safely.
+pub fn extract_method(
+    context: &AnalysisContext,
+    start_line: usize,
+    end_line: usize,
+    name: Option<&str>,
+) -> Result<Suggestion, String> {
+    let source = &context.source_code;
+    let offsets = line_start_offsets(source);
+    let line_count = offsets.len();
+
+    if start_line == 0 || end_line < start_line || end_line > line_count {
+        return Err(format!(
+            "line range {}:{} is out of bounds (file has {} lines)",
+            start_line, end_line, line_count
+        ));
+    }
+
+    let start_byte = offsets[start_line - 1];
+    let end_byte = offsets.get(end_line).copied().unwrap_or(source.len());
+    let range_text = &source[start_byte..end_byte];
+
+    if range_text.trim().is_empty() {
+        return Err("the selected range is empty".to_string());
+    }
+
+    let masked = mask_strings_and_comments(source);
+
+    if brace_balance(&masked[start_byte..end_byte]) != 0 {
+        return Err(
+            "the selected range spans a partial block (unbalanced braces); select whole statements"
+                .to_string(),
+        );
+    }
+
+    let range_tokens = tokenize_words(&masked[start_byte..end_byte]);
+    for keyword in ["return", "break", "continue"] {
+        if range_tokens.iter().any(|t| t == keyword) {
+            return Err(format!(
+                "the selected range contains `{}`, whose control flow can't be modeled by a generated call",
+                keyword
+            ));
+        }
+    }
+
+    let (sig_start, body_start, body_end) = find_enclosing_function(&masked, start_byte, end_byte)
+        .ok_or_else(|| "could not find an enclosing function for the selected range".to_string())?;
+
+    let param_names = parse_param_names(&source[sig_start..body_start]);
+    let before_tokens = tokenize_words(&masked[body_start..start_byte]);
+    let after_tokens = tokenize_words(&masked[end_byte..body_end]);
+
+    let already_defined: HashSet<String> = param_names
+        .into_iter()
+        .chain(let_bound_names(&before_tokens))
+        .collect();
+    let used_after: HashSet<String> = identifiers(&after_tokens).into_iter().collect();
+
+    let mut params = Vec::new();
+    let mut seen = HashSet::new();
+    for ident in identifiers(&range_tokens) {
+        if already_defined.contains(&ident) && seen.insert(ident.clone()) {
+            params.push(ident);
+        }
+    }
+
+    let mut returns = Vec::new();
+    let mut seen = HashSet::new();
+    for bound in let_bound_names(&range_tokens) {
+        if used_after.contains(&bound) && seen.insert(bound.clone()) {
+            returns.push(bound);
+        }
+    }
+
+    let fn_name = name.unwrap_or("extracted").to_string();
+    if source.contains(&format!("fn {}(", fn_name)) {
+        return Err(format!("a function named `{}` already exists", fn_name));
+    }
+
+    let new_fn_text = build_extracted_function(&fn_name, &params, &returns, range_text);
+    let indent = leading_whitespace(range_text);
+    let call_line = build_call_site(&fn_name, &params, &returns, &indent);
+
+    let close_line = source[..body_end].matches('\n').count() + 1;
+    let diff = build_diff(
+        source,
+        start_line,
+        end_line,
+        &call_line,
+        close_line,
+        &new_fn_text,
+    );
+
+    Ok(Suggestion {
+        title: format!("Extract method `{}`", fn_name),
+        description: format!(
+            "Extracts lines {}-{} into a new function `{}` taking {} parameter(s) and returning {} value(s). The new function's parameter and return types are left as generics for review.",
+            start_line,
+            end_line,
+            fn_name,
+            params.len(),
+            returns.len()
+        ),
+        refactoring_type: RefactoringType::ExtractMethod,
+        before_code: range_text.to_string(),
+        after_code: call_line,
+        impact: Impact::Medium,
+        fix_suggestion: Some(Fix {
+            description: format!("Extract lines {}-{} into `{}`", start_line, end_line, fn_name),
+            diff,
+            // Parameter/return types are placeholder generics, not
+            // inferred, so this always needs a human pass before landing.
+            safe_to_auto_apply: false,
+        }),
+    })
+}
+
+/// Byte offset of the start of each line (1-indexed line `n` starts at
+/// `offsets[n - 1]`).
+fn line_start_offsets(source: &str) -> Vec<usize> {
+    let mut offsets = vec![0];
+    for (i, c) in source.char_indices() {
+        if c == '\n' {
+            offsets.push(i + 1);
+        }
+    }
+    offsets
+}
+
+/// Net count of `{` minus `}` in (already masked) `text`.
+fn brace_balance(text: &str) -> i64 {
+    let mut depth = 0i64;
+    for c in text.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Split already-masked text into identifier runs and single-character
+/// punctuation tokens, the same shape `ssr`'s tokenizer uses.
+fn tokenize_words(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_alphanumeric() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn is_identifier(tok: &str) -> bool {
+    tok.chars()
+        .next()
+        .map(|c| c.is_alphabetic() || c == '_')
+        .unwrap_or(false)
+}
+
+/// Every identifier-shaped token in `tokens`, including keywords (callers
+/// narrow this down by intersecting with a known set of variable names).
+fn identifiers(tokens: &[String]) -> Vec<String> {
+    tokens.iter().filter(|t| is_identifier(t)).cloned().collect()
+}
+
+/// Names bound by a `let` or `let mut` in `tokens`, in order. Destructuring
+/// patterns (`let (a, b) = ...`, `let Point { x, y } = ...`) aren't
+/// recognized and are silently skipped, a known limitation of scanning
+/// tokens instead of a real AST.
+fn let_bound_names(tokens: &[String]) -> Vec<String> {
+    let mut names = Vec::new();
+    for (i, tok) in tokens.iter().enumerate() {
+        if tok != "let" {
+            continue;
+        }
+        let mut j = i + 1;
+        if tokens.get(j).map(String::as_str) == Some("mut") {
+            j += 1;
+        }
+        if let Some(candidate) = tokens.get(j) {
+            // `let Point { .. }` / `let Wrapper(..)`: a struct or tuple-struct
+            // pattern, not a plain binding.
+            let is_pattern = matches!(tokens.get(j + 1).map(String::as_str), Some("{") | Some("("));
+            if is_identifier(candidate) && !is_pattern {
+                names.push(candidate.clone());
+            }
+        }
+    }
+    names
+}
+
+/// Scan backward from `start_byte` over `masked` for the nearest `fn`
+/// whose brace-balanced body fully contains `[start_byte, end_byte)`.
+/// Returns `(signature_start, body_start, body_end)` byte offsets, where
+/// `body_start`/`body_end` bound the text between the function's `{` and
+/// its matching `}`.
+fn find_enclosing_function(masked: &str, start_byte: usize, end_byte: usize) -> Option<(usize, usize, usize)> {
+    for fn_pos in fn_keyword_positions(masked).into_iter().rev() {
+        if fn_pos >= start_byte {
+            continue;
+        }
+        let Some(open_rel) = masked[fn_pos..].find('{') else {
+            continue;
+        };
+        let open_pos = fn_pos + open_rel;
+        let body_start = open_pos + 1;
+        let Some(body_end) = find_matching_close(masked, open_pos) else {
+            continue;
+        };
+
+        if body_start <= start_byte && end_byte <= body_end {
+            return Some((fn_pos, body_start, body_end));
+        }
+    }
+    None
+}
+
+/// Byte positions of every whole-word `fn` keyword occurrence in `masked`.
+fn fn_keyword_positions(masked: &str) -> Vec<usize> {
+    let mut positions = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(rel) = masked[search_from..].find("fn") {
+        let pos = search_from + rel;
+        let before_ok = masked[..pos]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+        let after_ok = masked[pos + 2..]
+            .chars()
+            .next()
+            .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+
+        if before_ok && after_ok {
+            positions.push(pos);
+        }
+        search_from = pos + 2;
+    }
+
+    positions
+}
+
+/// Byte offset of the `}` matching the `{` at `open_pos`, scanning forward
+/// over `masked` and tracking brace depth.
+fn find_matching_close(masked: &str, open_pos: usize) -> Option<usize> {
+    let mut depth = 1;
+    for (i, c) in masked[open_pos + 1..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open_pos + 1 + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parameter names from a function signature's `(...)` parameter list,
+/// stripping `mut` and any type annotation. `self`/`&self`/`&mut self`
+/// become the single name `"self"`.
+fn parse_param_names(sig_text: &str) -> Vec<String> {
+    let Some(open_rel) = sig_text.find('(') else {
+        return Vec::new();
+    };
+
+    let mut depth = 0i32;
+    let mut close = None;
+    for (i, c) in sig_text[open_rel..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open_rel + i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return Vec::new();
+    };
+
+    split_top_level(&sig_text[open_rel + 1..close], ',')
+        .into_iter()
+        .filter_map(|chunk| {
+            let chunk = chunk.trim();
+            if chunk.is_empty() {
+                return None;
+            }
+            if chunk.ends_with("self") && !chunk.contains(':') {
+                return Some("self".to_string());
+            }
+            let name = chunk
+                .split(':')
+                .next()
+                .unwrap_or(chunk)
+                .trim()
+                .trim_start_matches("mut ")
+                .trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Split `text` on `sep` at bracket depth 0, so a parameter's generic type
+/// (`Vec<(A, B)>`) isn't mistaken for a parameter separator.
+fn split_top_level(text: &str, sep: char) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+
+    for c in text.chars() {
+        match c {
+            '(' | '<' | '[' | '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' | '>' | ']' | '}' => {
+                depth -= 1;
+                current.push(c);
+            }
+            _ if c == sep && depth == 0 => {
+                chunks.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Leading whitespace of the first line of `text`, used to indent the
+/// generated call site the same as the code it replaces.
+fn leading_whitespace(text: &str) -> String {
+    text.chars().take_while(|c| *c == ' ' || *c == '\t').collect()
+}
+
+/// Render the new function: a generic parameter/return type per inferred
+/// variable, the original range's statements (dedented and re-indented
+/// four spaces), and a trailing return expression if there are any
+/// returns.
+fn build_extracted_function(name: &str, params: &[String], returns: &[String], range_text: &str) -> String {
+    let type_params: Vec<String> = (1..=params.len()).map(|i| format!("T{}", i)).collect();
+    let return_types: Vec<String> = (1..=returns.len()).map(|i| format!("R{}", i)).collect();
+
+    let mut generics = type_params.clone();
+    generics.extend(return_types.clone());
+    let generics_clause = if generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", generics.join(", "))
+    };
+
+    let param_list: Vec<String> = params
+        .iter()
+        .zip(&type_params)
+        .map(|(name, ty)| format!("{}: {}", name, ty))
+        .collect();
+
+    let return_clause = match return_types.len() {
+        0 => String::new(),
+        1 => format!(" -> {}", return_types[0]),
+        _ => format!(" -> ({})", return_types.join(", ")),
+    };
+
+    let dedented = dedent(range_text);
+    let mut body = String::new();
+    for line in dedented.lines() {
+        if line.trim().is_empty() {
+            body.push('\n');
+        } else {
+            body.push_str("    ");
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if !returns.is_empty() {
+        body.push_str("    ");
+        if returns.len() == 1 {
+            body.push_str(&returns[0]);
+        } else {
+            body.push('(');
+            body.push_str(&returns.join(", "));
+            body.push(')');
+        }
+        body.push('\n');
+    }
+
+    format!(
+        "fn {}{}({}){} {{\n{}}}\n",
+        name,
+        generics_clause,
+        param_list.join(", "),
+        return_clause,
+        body
+    )
+}
+
+/// Strip the minimum common leading whitespace from every non-empty line.
+fn dedent(text: &str) -> String {
+    let common = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    text.lines()
+        .map(|l| if l.len() >= common { &l[common..] } else { l })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The line that replaces the extracted range: a bare call, or a `let`
+/// binding of its return value(s).
+fn build_call_site(name: &str, params: &[String], returns: &[String], indent: &str) -> String {
+    let args = params.join(", ");
+    match returns.len() {
+        0 => format!("{}{}({});", indent, name, args),
+        1 => format!("{}let {} = {}({});", indent, returns[0], name, args),
+        _ => format!("{}let ({}) = {}({});", indent, returns.join(", "), name, args),
+    }
+}
+
+/// Build the two-hunk unified diff: replace the extracted range with its
+/// call site, and insert the new function right after the enclosing
+/// function's closing brace.
+fn build_diff(
+    source: &str,
+    start_line: usize,
+    end_line: usize,
+    call_line: &str,
+    close_line: usize,
+    new_fn_text: &str,
+) -> String {
+    let removed: Vec<&str> = source
+        .lines()
+        .skip(start_line - 1)
+        .take(end_line - start_line + 1)
+        .collect();
+
+    let mut diff = String::new();
+    diff.push_str(&format!(
+        "@@ -{},{} +{},1 @@\n",
+        start_line,
+        removed.len(),
+        start_line
+    ));
+    for line in &removed {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    diff.push_str(&format!("+{}\n", call_line));
+
+    let close_line_text = source.lines().nth(close_line - 1).unwrap_or("}");
+    let new_fn_lines: Vec<&str> = new_fn_text.lines().collect();
+    diff.push_str(&format!(
+        "@@ -{},1 +{},{} @@\n",
+        close_line,
+        close_line,
+        2 + new_fn_lines.len()
+    ));
+    diff.push_str(&format!(" {}\n", close_line_text));
+    diff.push_str("+\n");
+    for line in &new_fn_lines {
+        diff.push_str(&format!("+{}\n", line));
+    }
+
+    diff
+}
@@ -0,0 +1,12 @@
+//! CodeSage Refactoring Library
+//!
+//! Refactoring suggestions and rule-based rewrites
+
+pub mod extract_method;
+mod masking;
+pub mod refactor;
+pub mod ssr;
+
+pub use extract_method::extract_method;
+pub use refactor::RefactoringEngine;
+pub use ssr::{apply_rules, SsrRule};
@@ -0,0 +1,80 @@
+//! Shared string/comment masking used by every token-scanning assist in
+//! this crate (`extract_method`, `ssr`) so none of them mistake text that
+//! merely *looks* like code inside a string literal or comment for the
+//! real thing.
+
+/// Blank out the contents of string literals and comments with
+/// same-byte-length runs of spaces, so brace/keyword/identifier scans
+/// don't trip over braces or keywords mentioned in text rather than code.
+/// Byte length and offsets are preserved exactly, so callers can keep
+/// slicing the original source by the byte ranges they compute here.
+/// Char literals are deliberately left alone since `'` also opens a
+/// lifetime, and misreading `'a` as a char literal would do more damage
+/// than the rare brace inside a char literal this would otherwise catch.
+pub(crate) fn mask_strings_and_comments(text: &str) -> String {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Code,
+        Str,
+        LineComment,
+        BlockComment,
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut state = State::Code;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match state {
+            State::Code => {
+                if c == '"' {
+                    state = State::Str;
+                    out.push(' ');
+                } else if c == '/' && chars.peek() == Some(&'/') {
+                    state = State::LineComment;
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                } else if c == '/' && chars.peek() == Some(&'*') {
+                    state = State::BlockComment;
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                } else {
+                    out.push(c);
+                }
+            }
+            State::Str => {
+                if c == '\\' {
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                    if let Some(escaped) = chars.next() {
+                        out.push_str(&" ".repeat(escaped.len_utf8()));
+                    }
+                } else if c == '"' {
+                    state = State::Code;
+                    out.push(' ');
+                } else if c == '\n' {
+                    out.push('\n');
+                } else {
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                }
+            }
+            State::LineComment => {
+                if c == '\n' {
+                    state = State::Code;
+                    out.push('\n');
+                } else {
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                }
+            }
+            State::BlockComment => {
+                if c == '*' && chars.peek() == Some(&'/') {
+                    state = State::Code;
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                } else if c == '\n' {
+                    out.push('\n');
+                } else {
+                    out.push_str(&" ".repeat(c.len_utf8()));
+                }
+            }
+        }
+    }
+
+    out
+}
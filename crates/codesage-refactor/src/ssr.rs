@@ -0,0 +1,412 @@
+//! Structured Search and Replace (SSR): syntactic rewrite rules of the
+//! shape `pattern ==>> template`, e.g. `foo($a, $b) ==>> bar($b, $a)`,
+//! inspired by rust-analyzer's `ssr` assist.
+//!
+//! `CodeParser` can produce a real tree-sitter `Tree` for languages with a
+//! registered grammar (see `codesage_parser::ParsedCode::tree`), but this
+//! module hasn't been migrated to walk actual AST subtrees the way
+//! rust-analyzer's implementation does. Until that lands, a rule's pattern
+//! and the source are both tokenized and matched as a flat token sequence
+//! instead: a `$var` in the pattern greedily
+//! captures source tokens up to the pattern's next literal token (tracking
+//! bracket nesting, so a `$var` can still capture a parenthesized or
+//! bracketed expression whole). That's enough to match call-shaped
+//! patterns like `foo($a, $b)` correctly without a grammar.
+
+use crate::masking::mask_strings_and_comments;
+use codesage_core::{AnalysisContext, Fix, Impact, Language, RefactoringType, Suggestion};
+use std::collections::{HashMap, HashSet};
+
+/// A token of pattern or source text, with its byte span in the original
+/// text so a match's captures can be sliced back out of the source.
+#[derive(Debug, Clone)]
+struct Token {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternToken {
+    /// A literal token the source must match exactly.
+    Literal(String),
+    /// A `$name` metavariable, bound to whatever source span it matches.
+    Var(String),
+}
+
+/// One parsed `pattern ==>> template` rule.
+pub struct SsrRule {
+    pattern: Vec<PatternToken>,
+    template: String,
+    /// `None` applies the rule to every language; `Some` restricts it to
+    /// one, e.g. a rule written against Rust call syntax.
+    language: Option<Language>,
+    /// Kept for the `Suggestion`'s description.
+    source_text: String,
+}
+
+impl SsrRule {
+    /// Parse a rule of the form `pattern ==>> template`. Rejects a rule
+    /// whose template references a `$var` the pattern never binds, since
+    /// that can never be substituted.
+    pub fn parse(rule_text: &str, language: Option<Language>) -> Result<Self, String> {
+        let (pattern_text, template_text) = rule_text
+            .split_once("==>>")
+            .ok_or_else(|| format!("rule has no `==>>` separator: {}", rule_text))?;
+        let pattern_text = pattern_text.trim();
+        let template_text = template_text.trim();
+
+        let pattern = parse_pattern(pattern_text);
+        let bound: HashSet<&str> = pattern
+            .iter()
+            .filter_map(|t| match t {
+                PatternToken::Var(name) => Some(name.as_str()),
+                PatternToken::Literal(_) => None,
+            })
+            .collect();
+
+        for var in template_vars(template_text) {
+            if !bound.contains(var.as_str()) {
+                return Err(format!(
+                    "template references unbound metavariable `${}`",
+                    var
+                ));
+            }
+        }
+
+        Ok(SsrRule {
+            pattern,
+            template: template_text.to_string(),
+            language,
+            source_text: rule_text.trim().to_string(),
+        })
+    }
+
+    fn applies_to(&self, language: Language) -> bool {
+        self.language.is_none_or(|l| l == language)
+    }
+}
+
+/// Run every rule whose language matches `context.language` against
+/// `context.source_code`, returning one `Suggestion` per match found,
+/// grouped rule by rule in the order given. Within one rule the matches
+/// don't overlap and come in source order; matches of different rules may
+/// overlap.
+pub fn apply_rules(context: &AnalysisContext, rules: &[SsrRule]) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    // Mask string/comment contents first so a pattern like `foo($a)` can't
+    // match text that only looks like a call inside a string literal or a
+    // `//`/`/* */` comment; byte offsets are preserved, so tokens built
+    // from the masked text still slice the right span out of the real
+    // source in `build_suggestion`.
+    let masked_source = mask_strings_and_comments(&context.source_code);
+
+    for rule in rules {
+        if !rule.applies_to(context.language) {
+            continue;
+        }
+
+        let tokens = tokenize(&masked_source);
+        let mut i = 0;
+        while i < tokens.len() {
+            match try_match(&tokens, i, &rule.pattern) {
+                Some((end, captures)) => {
+                    suggestions.push(build_suggestion(
+                        context,
+                        rule,
+                        &tokens[i],
+                        &tokens[end - 1],
+                        &captures,
+                    ));
+                    i = end.max(i + 1);
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    suggestions
+}
+
+fn build_suggestion(
+    context: &AnalysisContext,
+    rule: &SsrRule,
+    first: &Token,
+    last: &Token,
+    captures: &HashMap<String, (usize, usize)>,
+) -> Suggestion {
+    let source = &context.source_code;
+    let match_start = first.start;
+    let match_end = last.end;
+    let before_code = source[match_start..match_end].to_string();
+    let after_code = substitute_template(&rule.template, source, captures);
+
+    let fix = build_fix(source, match_start, match_end, &after_code, rule);
+
+    Suggestion {
+        title: format!("SSR rewrite: {}", rule.source_text),
+        description: format!(
+            "Matched `{}`; rewrites to `{}`.",
+            before_code.trim(),
+            after_code.trim()
+        ),
+        refactoring_type: RefactoringType::PatternRewrite,
+        before_code,
+        after_code,
+        impact: Impact::Low,
+        fix_suggestion: Some(fix),
+    }
+}
+
+/// Build a unified diff hunk replacing the whole lines the match spans
+/// (there is no line-level granularity finer than that in a unified
+/// diff), as a bare `@@ -a,b +c,d @@` hunk with no `--- a/`/`+++ b/` file
+/// headers, which the `Fix` apply engine in the CLI already parses.
+fn build_fix(source: &str, match_start: usize, match_end: usize, replacement: &str, rule: &SsrRule) -> Fix {
+    let line_start_byte = source[..match_start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end_byte = source[match_end..]
+        .find('\n')
+        .map(|i| match_end + i)
+        .unwrap_or(source.len());
+
+    let old_block = &source[line_start_byte..line_end_byte];
+    let new_block = format!(
+        "{}{}{}",
+        &source[line_start_byte..match_start],
+        replacement,
+        &source[match_end..line_end_byte]
+    );
+
+    let start_line = source[..line_start_byte].matches('\n').count() + 1;
+    let old_lines: Vec<&str> = old_block.split('\n').collect();
+    let new_lines: Vec<&str> = new_block.split('\n').collect();
+
+    let mut diff = String::new();
+    diff.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        start_line,
+        old_lines.len(),
+        start_line,
+        new_lines.len()
+    ));
+    for line in &old_lines {
+        diff.push_str(&format!("-{}\n", line));
+    }
+    for line in &new_lines {
+        diff.push_str(&format!("+{}\n", line));
+    }
+
+    Fix {
+        description: format!("Apply SSR rule `{}`", rule.source_text),
+        diff,
+        // SSR rewrites are syntactic, not semantic: they can't tell
+        // whether the rewrite preserves behavior, so they're never
+        // auto-applied without review.
+        safe_to_auto_apply: false,
+    }
+}
+
+fn substitute_template(template: &str, source: &str, captures: &HashMap<String, (usize, usize)>) -> String {
+    let mut result = String::new();
+    let mut chars = template.char_indices();
+
+    while let Some((idx, ch)) = chars.next() {
+        if ch != '$' {
+            result.push(ch);
+            continue;
+        }
+
+        let rest = &template[idx + 1..];
+        let name_chars = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_');
+        let name_char_count = name_chars.clone().count();
+        let name_len: usize = name_chars.map(char::len_utf8).sum();
+
+        if name_len == 0 {
+            result.push('$');
+            continue;
+        }
+
+        let name = &rest[..name_len];
+        if let Some(&(start, end)) = captures.get(name) {
+            result.push_str(&source[start..end]);
+        } else {
+            // Already validated at parse time, but fall back to the
+            // literal text rather than panicking if something's off.
+            result.push('$');
+            result.push_str(name);
+        }
+
+        for _ in 0..name_char_count {
+            chars.next();
+        }
+    }
+
+    result
+}
+
+/// Names of every `$var` referenced in `template`.
+fn template_vars(template: &str) -> Vec<String> {
+    let mut vars = Vec::new();
+    let mut chars = template.char_indices();
+
+    while let Some((idx, ch)) = chars.next() {
+        if ch != '$' {
+            continue;
+        }
+        let rest = &template[idx + 1..];
+        let name: String = rest
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .collect();
+        if !name.is_empty() {
+            for _ in 0..name.chars().count() {
+                chars.next();
+            }
+            vars.push(name);
+        }
+    }
+
+    vars
+}
+
+fn parse_pattern(pattern_text: &str) -> Vec<PatternToken> {
+    tokenize(pattern_text)
+        .into_iter()
+        .map(|tok| {
+            if let Some(name) = tok.text.strip_prefix('$') {
+                PatternToken::Var(name.to_string())
+            } else {
+                PatternToken::Literal(tok.text)
+            }
+        })
+        .collect()
+}
+
+/// Try to match `pattern` against `tokens` starting at `start`. Returns
+/// the index just past the match and each `$var`'s captured byte span.
+fn try_match(
+    tokens: &[Token],
+    start: usize,
+    pattern: &[PatternToken],
+) -> Option<(usize, HashMap<String, (usize, usize)>)> {
+    let mut si = start;
+    let mut pi = 0;
+    let mut captures = HashMap::new();
+
+    while pi < pattern.len() {
+        match &pattern[pi] {
+            PatternToken::Literal(text) => {
+                if si >= tokens.len() || tokens[si].text != *text {
+                    return None;
+                }
+                si += 1;
+                pi += 1;
+            }
+            PatternToken::Var(name) => {
+                let next_literal = match pattern.get(pi + 1) {
+                    Some(PatternToken::Literal(text)) => Some(text.as_str()),
+                    _ => None,
+                };
+
+                let capture_start = si;
+                let mut depth = 0i32;
+
+                loop {
+                    if si >= tokens.len() {
+                        break;
+                    }
+                    let text = tokens[si].text.as_str();
+
+                    if depth == 0 {
+                        if let Some(lit) = next_literal {
+                            if text == lit {
+                                break;
+                            }
+                        }
+                        if is_close_bracket(text) {
+                            break;
+                        }
+                    }
+
+                    if is_open_bracket(text) {
+                        depth += 1;
+                    } else if is_close_bracket(text) {
+                        depth -= 1;
+                    }
+                    si += 1;
+                }
+
+                let start_byte = tokens
+                    .get(capture_start)
+                    .map(|t| t.start)
+                    .unwrap_or_else(|| tokens.last().map(|t| t.end).unwrap_or(0));
+                let end_byte = if si > capture_start {
+                    tokens[si - 1].end
+                } else {
+                    start_byte
+                };
+                captures.insert(name.clone(), (start_byte, end_byte));
+                pi += 1;
+            }
+        }
+    }
+
+    Some((si, captures))
+}
+
+fn is_open_bracket(text: &str) -> bool {
+    matches!(text, "(" | "[" | "{")
+}
+
+fn is_close_bracket(text: &str) -> bool {
+    matches!(text, ")" | "]" | "}")
+}
+
+/// Split `text` into identifier/number runs and individual punctuation
+/// characters, skipping whitespace. Good enough to match call-shaped
+/// patterns without needing a real grammar; see the module doc comment.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let ch = text[i..].chars().next().unwrap();
+
+        if ch.is_whitespace() {
+            i += ch.len_utf8();
+            continue;
+        }
+
+        if ch == '$' || ch.is_alphanumeric() || ch == '_' {
+            let start = i;
+            let mut j = i + ch.len_utf8();
+            while j < bytes.len() {
+                let c = text[j..].chars().next().unwrap();
+                if c.is_alphanumeric() || c == '_' {
+                    j += c.len_utf8();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token {
+                text: text[start..j].to_string(),
+                start,
+                end: j,
+            });
+            i = j;
+            continue;
+        }
+
+        let start = i;
+        let end = i + ch.len_utf8();
+        tokens.push(Token {
+            text: text[start..end].to_string(),
+            start,
+            end,
+        });
+        i = end;
+    }
+
+    tokens
+}
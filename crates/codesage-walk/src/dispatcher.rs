@@ -0,0 +1,93 @@
+//! Work-queue dispatch of file analysis across worker threads.
+
+use crate::reporter::FileReport;
+use crate::walker::WalkedFile;
+use codesage_analyzer::AnalysisEngine;
+use codesage_core::AnalysisContext;
+use codesage_parser::CodeParser;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Fans file analysis out across a fixed pool of worker threads. Each
+/// worker builds its own `CodeParser` rather than sharing one, since
+/// `tree_sitter::Parser` isn't `Sync`, and pulls the next unclaimed file
+/// from a shared index instead of a static chunk, so one huge file doesn't
+/// leave other workers idle waiting on a statically-assigned partner.
+pub struct Dispatcher {
+    num_workers: usize,
+}
+
+impl Dispatcher {
+    /// Dispatch across exactly `num_workers` threads (clamped to at least 1).
+    pub fn new(num_workers: usize) -> Self {
+        Self {
+            num_workers: num_workers.max(1),
+        }
+    }
+
+    /// Dispatch across one worker per available CPU, falling back to a
+    /// single worker if that can't be determined.
+    pub fn with_available_parallelism() -> Self {
+        let num_workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        Self::new(num_workers)
+    }
+
+    /// Analyze every file in `files` with a fresh `build_engine()` per
+    /// worker thread, returning one `FileReport` per file that parsed and
+    /// analyzed successfully. A file that fails to parse or analyze is
+    /// silently dropped rather than aborting the whole review, matching
+    /// `MetricsAnalyzer`'s own per-file fallback conventions elsewhere in
+    /// this crate family.
+    pub fn run(
+        &self,
+        files: &[WalkedFile],
+        build_engine: impl Fn() -> AnalysisEngine + Send + Sync,
+    ) -> Vec<FileReport> {
+        let next_index = AtomicUsize::new(0);
+        let results = Mutex::new(Vec::with_capacity(files.len()));
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.num_workers {
+                scope.spawn(|| {
+                    let mut parser = CodeParser::new();
+                    let engine = build_engine();
+
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        let Some(file) = files.get(index) else {
+                            break;
+                        };
+
+                        if let Some(report) = analyze_file(&mut parser, &engine, file) {
+                            results.lock().unwrap().push(report);
+                        }
+                    }
+                });
+            }
+        });
+
+        results.into_inner().unwrap()
+    }
+}
+
+fn analyze_file(
+    parser: &mut CodeParser,
+    engine: &AnalysisEngine,
+    file: &WalkedFile,
+) -> Option<FileReport> {
+    let parsed = parser.parse_file(&file.path).ok()?;
+
+    let context = AnalysisContext {
+        file_path: file.path.clone(),
+        source_code: parsed.source().to_string(),
+        language: parsed.language,
+    };
+
+    let issues = engine.analyze(&context).ok()?;
+    Some(FileReport {
+        file_path: file.path.clone(),
+        issues,
+    })
+}
@@ -0,0 +1,31 @@
+//! CodeSage Walk Library
+//!
+//! Turns a single-file `Analyzer` pass into a whole-project review: `Walker`
+//! recursively finds source files honoring `.gitignore`/hidden-file rules,
+//! `Dispatcher` fans analysis of those files out across worker threads, and
+//! `Reporter` collects the results into one sorted, repo-level `Report`.
+
+mod dispatcher;
+mod reporter;
+mod walker;
+
+pub use dispatcher::Dispatcher;
+pub use reporter::{FileReport, Report, Reporter};
+pub use walker::{WalkedFile, Walker};
+
+use codesage_analyzer::AnalysisEngine;
+use std::path::Path;
+
+/// Walk `root`, analyze every file found with `build_engine`'s analyzers
+/// across `Dispatcher::with_available_parallelism`'s worker threads, and
+/// return the aggregated `Report`. The convenience entry point a caller
+/// that doesn't need to customize the walk or dispatch should reach for
+/// first; see `Walker`/`Dispatcher`/`Reporter` for the pieces this composes.
+pub fn review_directory(
+    root: &Path,
+    build_engine: impl Fn() -> AnalysisEngine + Send + Sync,
+) -> Report {
+    let files = Walker::walk(root);
+    let file_reports = Dispatcher::with_available_parallelism().run(&files, build_engine);
+    Reporter::build(file_reports)
+}
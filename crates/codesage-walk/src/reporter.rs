@@ -0,0 +1,49 @@
+//! Aggregating per-file analysis results into a repo-level report.
+
+use codesage_core::{Issue, Severity};
+use std::path::PathBuf;
+
+/// One file's analysis result, as produced by `crate::Dispatcher::run`.
+pub struct FileReport {
+    pub file_path: PathBuf,
+    pub issues: Vec<Issue>,
+}
+
+/// A whole-project review: every file's issues flattened into one list,
+/// sorted by file path and then by severity (most critical first) within
+/// each file.
+pub struct Report {
+    pub issues: Vec<Issue>,
+}
+
+/// Collects `FileReport`s from `Dispatcher::run` into a single `Report`.
+pub struct Reporter;
+
+impl Reporter {
+    /// Flatten and sort `file_reports` into a `Report`.
+    pub fn build(file_reports: Vec<FileReport>) -> Report {
+        let mut issues: Vec<Issue> = file_reports
+            .into_iter()
+            .flat_map(|report| report.issues)
+            .collect();
+
+        issues.sort_by(|a, b| {
+            a.location
+                .file_path
+                .cmp(&b.location.file_path)
+                .then_with(|| severity_rank(a.severity).cmp(&severity_rank(b.severity)))
+        });
+
+        Report { issues }
+    }
+}
+
+/// Lower is more critical, so sorting by this ascending puts P0s first.
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::P0 => 0,
+        Severity::P1 => 1,
+        Severity::P2 => 2,
+        Severity::P3 => 3,
+    }
+}
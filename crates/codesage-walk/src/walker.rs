@@ -0,0 +1,39 @@
+//! Ignore-aware directory traversal.
+
+use codesage_core::Language;
+use std::path::{Path, PathBuf};
+
+/// A source file `Walker::walk` found, already matched to the `Language`
+/// its extension implies.
+pub struct WalkedFile {
+    pub path: PathBuf,
+    pub language: Language,
+}
+
+/// Recursively finds source files under a directory.
+pub struct Walker;
+
+impl Walker {
+    /// Every file under `root` whose extension maps to a known `Language`
+    /// (see `Language::from_extension`), skipping whatever `.gitignore`/
+    /// `.ignore`/hidden-file rules the `ignore` crate's default builder
+    /// applies, with `.gitignore` honored even outside a git repository,
+    /// so a review never flags generated or vendored files a project has
+    /// already chosen to ignore.
+    pub fn walk(root: &Path) -> Vec<WalkedFile> {
+        ignore::WalkBuilder::new(root)
+            .require_git(false)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+            .filter_map(|entry| {
+                let path = entry.into_path();
+                let language = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(Language::from_extension)?;
+                Some(WalkedFile { path, language })
+            })
+            .collect()
+    }
+}
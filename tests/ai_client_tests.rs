@@ -12,6 +12,7 @@ mod tests {
         assert_eq!(config.model, "claude-3-5-sonnet-20241022");
         assert_eq!(config.api_base_url, "https://api.anthropic.com/v1");
         assert_eq!(config.timeout_seconds, 60);
+        assert_eq!(config.context_window_tokens, 180_000);
         // API 密钥可能为空,这取决于环境变量
     }
 
@@ -30,6 +31,8 @@ mod tests {
             model: "custom-model".to_string(),
             api_base_url: "https://api.test.com/v1".to_string(),
             timeout_seconds: 30,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
         };
         let _client = codesage_ai::AIClient::with_config(config);
         // 验证自定义配置的客户端可以成功创建，不会panic
@@ -45,6 +48,8 @@ mod tests {
             model: "claude-3-5-sonnet-20241022".to_string(),
             api_base_url: "https://api.anthropic.com/v1".to_string(),
             timeout_seconds: 60,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
         };
 
         let client = codesage_ai::AIClient::with_config(config);
@@ -70,6 +75,8 @@ mod tests {
             model: "claude-3-5-sonnet-20241022".to_string(),
             api_base_url: "https://api.anthropic.com/v1".to_string(),
             timeout_seconds: 120,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
         };
 
         assert!(config.timeout_seconds > 0);
@@ -85,6 +92,8 @@ mod tests {
             model: "claude-3-5-sonnet-20241022".to_string(),
             api_base_url: "https://api.anthropic.com/v1".to_string(),
             timeout_seconds: 10,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
         };
 
         let long_timeout = codesage_ai::AIConfig {
@@ -92,6 +101,8 @@ mod tests {
             model: "claude-3-5-sonnet-20241022".to_string(),
             api_base_url: "https://api.anthropic.com/v1".to_string(),
             timeout_seconds: 300,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
         };
 
         assert!(short_timeout.timeout_seconds >= 10);
@@ -113,4 +124,135 @@ mod tests {
         assert!(config.model.contains("claude"));
         assert!(!config.model.is_empty());
     }
+
+    /// 测试小文件不会被拆分成多个窗口
+    #[test]
+    fn test_chunking_keeps_small_file_in_one_window() {
+        let source = "fn main() {\n    println!(\"hi\");\n}";
+        let windows = codesage_ai::chunking::split_into_windows(source, 10_000);
+
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].start_line, 1);
+        assert_eq!(windows[0].text, source);
+    }
+
+    /// 测试超过预算的大文件会被拆分为多个有重叠的窗口
+    #[test]
+    fn test_chunking_splits_large_file_with_overlap() {
+        let source = (0..500)
+            .map(|i| format!("let line_{} = {};", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let windows = codesage_ai::chunking::split_into_windows(&source, 200);
+
+        assert!(windows.len() > 1);
+        // 每个窗口的 token 数都应当在预算附近（不超过太多）
+        for window in &windows {
+            assert!(codesage_ai::chunking::count_tokens(&window.text) <= 200 * 2);
+        }
+        // 相邻窗口之间应当有重叠，避免遗漏跨边界的问题
+        assert!(windows[1].start_line < windows[0].text.lines().count() + 1);
+    }
+
+    /// 测试即使单行本身就超过预算，也至少会被作为一个窗口返回，而不是产生空窗口
+    #[test]
+    fn test_chunking_keeps_oversized_single_line_as_its_own_window() {
+        let huge_line = "x".repeat(5_000);
+        let windows = codesage_ai::chunking::split_into_windows(&huge_line, 1);
+
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].text, huge_line);
+    }
+
+    /// 测试拆分后的窗口首尾相接能覆盖整个文件，不会丢失末尾的内容
+    #[test]
+    fn test_chunking_windows_cover_whole_file() {
+        let source = (0..500)
+            .map(|i| format!("let line_{} = {};", i, i))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let windows = codesage_ai::chunking::split_into_windows(&source, 200);
+        let last = windows.last().unwrap();
+        assert!(last.text.ends_with("let line_499 = 499;"));
+    }
+
+    /// 测试空字符串的 token 数为 0
+    #[test]
+    fn test_count_tokens_empty_string() {
+        assert_eq!(codesage_ai::chunking::count_tokens(""), 0);
+    }
+
+    /// 测试 Anthropic provider 在未设置 API key 时,complete/embed 均应在
+    /// 发出网络请求之前返回明确的错误,而不是 panic 或静默失败
+    #[tokio::test]
+    async fn test_anthropic_provider_requires_api_key() {
+        use codesage_core::AIReviewer;
+
+        let config = codesage_ai::AIConfig {
+            api_key: None,
+            model: "claude-3-5-sonnet-20241022".to_string(),
+            api_base_url: "https://api.anthropic.com/v1".to_string(),
+            timeout_seconds: 5,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::Anthropic,
+        };
+        let client = codesage_ai::AIClient::with_config(config);
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn main() {}".to_string(),
+            language: Language::Rust,
+        };
+
+        // `review` swallows provider errors into an empty issue list rather
+        // than propagating them (see AIClient::review), so assert via the
+        // lower-level `embed` call that actually surfaces the error.
+        let err = client.embed("some code").await.unwrap_err();
+        assert!(format!("{}", err).contains("ANTHROPIC_API_KEY"));
+
+        // The review path itself should still succeed gracefully.
+        let result = client.review(&context).await;
+        assert!(result.is_ok());
+    }
+
+    /// 测试 OpenAI provider 在未设置 API key 时同样在请求前报错
+    #[tokio::test]
+    async fn test_openai_provider_requires_api_key() {
+        let config = codesage_ai::AIConfig {
+            api_key: None,
+            model: "gpt-4o".to_string(),
+            api_base_url: "https://api.openai.com/v1".to_string(),
+            timeout_seconds: 5,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::OpenAi,
+        };
+        let client = codesage_ai::AIClient::with_config(config);
+
+        let err = client.embed("some code").await.unwrap_err();
+        assert!(format!("{}", err).contains("OPENAI_API_KEY"));
+    }
+
+    /// 测试 OpenAI 兼容 provider 不要求 API key：没有 key 时会直接尝试发出
+    /// 请求（在本地不可达端口上快速失败），而不是像 OpenAi/Anthropic 那样
+    /// 提前因缺少 key 报错
+    #[tokio::test]
+    async fn test_openai_compatible_provider_does_not_require_api_key() {
+        let config = codesage_ai::AIConfig {
+            api_key: None,
+            model: "local-model".to_string(),
+            // Port 9 (discard) has nothing listening, so this fails fast
+            // with a connection error instead of hanging or succeeding.
+            api_base_url: "http://127.0.0.1:9".to_string(),
+            timeout_seconds: 2,
+            context_window_tokens: 180_000,
+            provider: codesage_ai::Provider::OpenAiCompatible,
+        };
+        let client = codesage_ai::AIClient::with_config(config);
+
+        let err = client.embed("some code").await.unwrap_err();
+        // It got far enough to attempt the request instead of bailing out
+        // on a missing API key.
+        assert!(!format!("{}", err).contains("API_KEY not set"));
+    }
 }
@@ -98,6 +98,87 @@ mod tests {
         assert!(metrics.duplication_percentage > 0.0);
     }
 
+    /// 测试跨文件克隆检测对 3 份以上重复代码只报告一个合并后的分组
+    #[test]
+    fn test_cross_file_duplication_merges_three_copies_into_one_group() {
+        let block = r#"
+        fn duplicated_work(x: i32, y: i32, z: i32) -> i32 {
+            let a = x + y;
+            let b = y + z;
+            let c = z + x;
+            let d = a + b;
+            let e = b + c;
+            let f = c + d;
+            let g = d + e;
+            let h = e + f;
+            let i = f + g;
+            let j = g + h;
+            let k = h + i;
+            let l = i + j;
+            let m = j + k;
+            let n = k + l;
+            let o = l + m;
+            a + b + c + d + e + f + g + h + i + j + k + l + m + n + o
+        }
+        "#;
+
+        let sources = vec![
+            codesage_analyzer::DuplicationSource {
+                file_path: PathBuf::from("a.rs"),
+                source_code: block.to_string(),
+            },
+            codesage_analyzer::DuplicationSource {
+                file_path: PathBuf::from("b.rs"),
+                source_code: block.to_string(),
+            },
+            codesage_analyzer::DuplicationSource {
+                file_path: PathBuf::from("c.rs"),
+                source_code: block.to_string(),
+            },
+        ];
+
+        let issues = codesage_analyzer::detect_duplication_across_files(&sources);
+
+        // 3 份重复应合并为一个分组/Issue，而不是 (a,b)/(a,c)/(b,c) 三个重复的 Issue
+        assert_eq!(issues.len(), 1);
+        // 一个位置作为主位置，其余 2 份拷贝放在 related_locations 中
+        assert_eq!(issues[0].related_locations.len(), 2);
+    }
+
+    /// 测试 ABC 规模：函数体内只有赋值、没有分支/条件，但 `function_abc_counts`
+    /// 统计的是包含 `fn name(...) {` 签名行在内的整段函数文本，而签名行里函数
+    /// 自身的名字后面跟着 `(` 会被当成一次调用/分支（只有 `let`/`if` 等
+    /// `CONTROL_KEYWORDS` 才会被排除在外），所以量级是 sqrt(3 次赋值^2 + 1 次
+    /// 分支^2) 而不是单纯的赋值数。
+    #[test]
+    fn test_abc_size_counts_assignments_only() {
+        let analyzer = codesage_analyzer::MetricsAnalyzer::new();
+        let code = "fn f() { let mut a = 1; a += 1; a -= 1; }";
+        let metrics = analyzer.calculate_metrics(code);
+        assert!((metrics.abc_size - 10.0_f32.sqrt()).abs() < 0.01);
+    }
+
+    /// 测试高 ABC 规模的函数会触发 ABC001 问题
+    #[test]
+    fn test_high_abc_size_detection() {
+        let analyzer = codesage_analyzer::MetricsAnalyzer::with_thresholds(
+            codesage_analyzer::MetricsThresholds {
+                abc_size_warning: 1.0,
+                abc_size_error: 2.0,
+                ..Default::default()
+            },
+        );
+        let code = "fn f(x: i32) -> i32 { let a = x + 1; let b = a.abs(); if b > 0 { b } else { a } }";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        assert!(issues.iter().any(|issue| issue.id == "ABC001"));
+    }
+
     /// 测试分析器接口
     #[test]
     fn test_analyzer_trait() {
@@ -177,4 +258,154 @@ mod tests {
         // 简单代码不应产生太多问题
         assert!(issues.len() < 5);
     }
+
+    /// 测试 SyntaxAnalyzer 能将语法错误报告为带位置信息的 Issue
+    #[test]
+    fn test_syntax_analyzer_reports_parse_error_as_issue() {
+        let analyzer = codesage_analyzer::SyntaxAnalyzer::new();
+
+        let context = AnalysisContext {
+            file_path: PathBuf::from("broken.rs"),
+            source_code: "fn broken( {".to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        assert!(!issues.is_empty());
+        assert_eq!(issues[0].id, "SYNTAX001");
+        assert_eq!(issues[0].location.file_path, PathBuf::from("broken.rs"));
+    }
+
+    /// 测试 SyntaxAnalyzer 对无语法错误的代码不产生 Issue
+    #[test]
+    fn test_syntax_analyzer_clean_source_has_no_issues() {
+        let analyzer = codesage_analyzer::SyntaxAnalyzer::new();
+
+        let context = AnalysisContext {
+            file_path: PathBuf::from("clean.rs"),
+            source_code: "fn add(a: i32, b: i32) -> i32 { a + b }".to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        assert!(issues.is_empty());
+    }
+
+    /// 测试 AST 驱动的圈复杂度不被字符串/注释中的关键字误导
+    #[test]
+    fn test_ast_cyclomatic_ignores_strings_and_comments() {
+        let analyzer = codesage_analyzer::MetricsAnalyzer::new();
+
+        let code = r#"fn f() {
+            // if this comment mentioned if/while/for it must not count
+            let s = "if true { while true { for x in y {} } }";
+            if s.is_empty() {
+                println!("empty");
+            }
+        }"#;
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        // 唯一真正的决策点是那一个 if，不应触发高复杂度问题
+        assert!(!issues.iter().any(|issue| issue.message.contains("complexity")));
+    }
+
+    /// 测试 AST 驱动的认知复杂度遵循 SonarSource 的嵌套规则
+    #[test]
+    fn test_ast_cognitive_nesting_and_else_if() {
+        let analyzer = codesage_analyzer::MetricsAnalyzer::new();
+
+        // if(1) + nested if(1+1) + else(1) + else-if(1) + boolean run(1) = 6,
+        // well below the default threshold of 15
+        let code = r#"fn f(a: bool, b: bool, c: bool) {
+            if a {
+                if b {
+                    println!("nested");
+                } else {
+                    println!("else");
+                }
+            } else if a && b && c {
+                println!("chained");
+            }
+        }"#;
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        // 不应崩溃，且不应对这种适度嵌套的代码产生告警
+        assert!(issues.iter().all(|issue| !issue.message.contains("High cognitive")));
+    }
+
+    /// 测试从规则文件文本解析 QueryRule
+    #[test]
+    fn test_query_rule_from_rule_text() {
+        let rule_text = r#"
+;; id: NO_UNWRAP
+;; severity: P2
+;; category: Maintainability
+;; capture: call
+;; message: avoid `.unwrap()` in {call}
+
+(call_expression
+  function: (field_expression field: (field_identifier) @method)
+  (#eq? @method "unwrap")) @call
+"#;
+        let rule = codesage_analyzer::QueryRule::from_rule_text(rule_text).unwrap();
+        assert_eq!(rule.id, "NO_UNWRAP");
+        assert_eq!(rule.anchor_capture, "call");
+        assert!(rule.message_template.contains("{call}"));
+    }
+
+    /// 测试 QueryAnalyzer 根据自定义查询规则产生问题
+    #[test]
+    fn test_query_analyzer_matches_rule() {
+        let rule_text = r#"
+;; id: NO_UNWRAP
+;; severity: P2
+;; category: Maintainability
+;; capture: call
+;; message: avoid `.unwrap()`
+
+(call_expression
+  function: (field_expression field: (field_identifier) @method)
+  (#eq? @method "unwrap")) @call
+"#;
+        let rule = codesage_analyzer::QueryRule::from_rule_text(rule_text).unwrap();
+        let analyzer = codesage_analyzer::QueryAnalyzer::new(Language::Rust, vec![rule]).unwrap();
+
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn f() { let x = bar.unwrap(); }".to_string(),
+            language: Language::Rust,
+        };
+
+        let issues = analyzer.analyze(&context).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].id, "NO_UNWRAP");
+    }
+
+    /// 测试 Halstead 指标：手工按词法规则数出 operator/operand 计数，
+    /// 验证 volume/difficulty/effort 与标准公式吻合
+    #[test]
+    fn test_halstead_metrics_match_standard_formulas() {
+        let analyzer = codesage_analyzer::MetricsAnalyzer::new();
+        // operators: fn ( ) { let = ; } -> 8 distinct, 8 total
+        // operands: f a 1 -> 3 distinct, 3 total
+        let code = "fn f() { let a = 1; }";
+        let metrics = analyzer.calculate_metrics(code);
+
+        // n = 11, N = 11, V = N * log2(n)
+        assert!((metrics.halstead_volume - 38.054).abs() < 0.01);
+        // D = (n1/2) * (N2/n2) = (8/2) * (3/3)
+        assert!((metrics.halstead_difficulty - 4.0).abs() < 0.01);
+        // E = D * V
+        assert!((metrics.halstead_effort - 152.215).abs() < 0.01);
+    }
 }
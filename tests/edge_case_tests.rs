@@ -16,7 +16,7 @@ mod edge_case_tests {
             .collect::<Vec<_>>()
             .join("\n");
 
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let parsed = parser.parse_source(&large_content, Language::Rust).unwrap();
 
         assert_eq!(parsed.line_count(), 1000);
@@ -25,7 +25,7 @@ mod edge_case_tests {
     /// 测试空字符串
     #[test]
     fn test_empty_string() {
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let parsed = parser.parse_source("", Language::Rust).unwrap();
 
         assert!(parsed.is_empty());
@@ -35,7 +35,7 @@ mod edge_case_tests {
     /// 测试只包含空白字符的字符串
     #[test]
     fn test_whitespace_only() {
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let parsed = parser
             .parse_source("   \n\t\n  \n", Language::Rust)
             .unwrap();
@@ -119,7 +119,7 @@ mod edge_case_tests {
         writeln!(temp_file, "fn test() {{ println!(\"hello\"); }}").unwrap();
         temp_file.flush().unwrap();
 
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let result = parser.parse_file(temp_file.path());
 
         // 应该成功解析临时文件
@@ -20,7 +20,7 @@ mod tests {
     fn test_empty_file() {
         let temp_file = NamedTempFile::with_suffix(".rs").unwrap();
 
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let result = parser.parse_file(temp_file.path());
 
         assert!(
@@ -36,7 +36,7 @@ mod tests {
     #[test]
     fn test_parse_source() {
         let source = "fn test() { let x = 1; }";
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let result = parser.parse_source(source, Language::Rust);
 
         assert!(result.is_ok());
@@ -49,9 +49,89 @@ mod tests {
     #[test]
     fn test_line_count() {
         let source = "line1\nline2\nline3";
-        let parser = codesage_parser::CodeParser::new();
+        let mut parser = codesage_parser::CodeParser::new();
         let parsed = parser.parse_source(source, Language::Rust).unwrap();
 
         assert_eq!(parsed.line_count(), 3);
     }
+
+    /// 测试 Rust 源码能生成真实语法树且无错误节点
+    #[test]
+    fn test_parse_source_produces_real_tree_for_rust() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let mut parser = codesage_parser::CodeParser::new();
+        let parsed = parser.parse_source(source, Language::Rust).unwrap();
+
+        let root = parsed.root_node().expect("rust grammar should be registered");
+        assert_eq!(root.kind(), "source_file");
+        assert!(!parsed.has_errors());
+    }
+
+    /// 测试语法错误的源码会被标记为含有错误节点
+    #[test]
+    fn test_parse_source_flags_syntax_errors() {
+        let source = "fn broken( {";
+        let mut parser = codesage_parser::CodeParser::new();
+        let parsed = parser.parse_source(source, Language::Rust).unwrap();
+
+        assert!(parsed.has_errors());
+    }
+
+    /// 测试增量重新解析能反映编辑后的源码且无语法错误
+    #[test]
+    fn test_apply_edit_and_reparse() {
+        let old_source = "fn a() { let x = 1; }\nfn b() { let y = 2; }\n";
+        let mut parser = codesage_parser::CodeParser::new();
+        let mut parsed = parser.parse_source(old_source, Language::Rust).unwrap();
+        assert!(!parsed.has_errors());
+
+        let start_byte = old_source.find("1;").unwrap();
+        let old_end_byte = start_byte + 1;
+        let new_source = format!(
+            "{}100{}",
+            &old_source[..start_byte],
+            &old_source[old_end_byte..]
+        );
+        let new_end_byte = start_byte + 3;
+
+        parsed.apply_edit(start_byte, old_end_byte, new_end_byte, &new_source);
+        let reparsed = parser.reparse(&parsed).unwrap();
+
+        assert_eq!(reparsed.source(), new_source);
+        assert!(!reparsed.has_errors());
+    }
+
+    /// 测试对没有已解析语法树的代码应用编辑不会 panic
+    #[test]
+    fn test_apply_edit_without_prior_tree() {
+        let mut parsed = codesage_parser::CodeParser::new()
+            .parse_source("irrelevant", Language::Go)
+            .unwrap();
+
+        parsed.apply_edit(0, 0, 3, "newtext");
+        assert_eq!(parsed.source(), "newtext");
+    }
+
+    /// 测试语法错误的源码仍能得到带位置信息的诊断，而不是直接失败
+    #[test]
+    fn test_diagnostics_reports_span_for_syntax_error() {
+        let source = "fn broken( {";
+        let mut parser = codesage_parser::CodeParser::new();
+        let parsed = parser.parse_source(source, Language::Rust).unwrap();
+
+        let diagnostics = parsed.diagnostics();
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0].start_line, 1);
+        assert!(!diagnostics[0].message.is_empty());
+    }
+
+    /// 测试无语法错误的源码不产生诊断
+    #[test]
+    fn test_diagnostics_empty_for_clean_source() {
+        let source = "fn add(a: i32, b: i32) -> i32 { a + b }";
+        let mut parser = codesage_parser::CodeParser::new();
+        let parsed = parser.parse_source(source, Language::Rust).unwrap();
+
+        assert!(parsed.diagnostics().is_empty());
+    }
 }
@@ -0,0 +1,166 @@
+//! Refactor 模块单元测试
+
+#[cfg(test)]
+mod tests {
+    use codesage_core::{AnalysisContext, Language};
+    use codesage_refactor::{apply_rules, extract_method, SsrRule};
+    use std::path::PathBuf;
+
+    /// 测试 SSR 规则能正确解析 `pattern ==>> template` 形式
+    #[test]
+    fn test_ssr_rule_parse_valid() {
+        let rule = SsrRule::parse("foo($a, $b) ==>> bar($b, $a)", None);
+        assert!(rule.is_ok());
+    }
+
+    /// 测试没有 `==>>` 分隔符的规则会被拒绝
+    #[test]
+    fn test_ssr_rule_parse_rejects_missing_separator() {
+        let rule = SsrRule::parse("foo($a, $b)", None);
+        assert!(rule.is_err());
+    }
+
+    /// 测试模板引用了模式中未绑定的变量时会被拒绝
+    #[test]
+    fn test_ssr_rule_parse_rejects_unbound_template_var() {
+        let rule = SsrRule::parse("foo($a) ==>> bar($a, $c)", None);
+        assert!(rule.is_err());
+    }
+
+    /// 测试基本的 SSR 重写：交换参数顺序
+    #[test]
+    fn test_apply_rules_matches_and_swaps_args() {
+        let rule = SsrRule::parse("foo($a, $b) ==>> bar($b, $a)", None).unwrap();
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn main() { foo(1, 2); }".to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestions = apply_rules(&context, &[rule]);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].before_code, "foo(1, 2)");
+        assert_eq!(suggestions[0].after_code, "bar(2, 1)");
+    }
+
+    /// 测试 `$var` 能捕获带括号的整个表达式，而不会被内部的逗号截断
+    #[test]
+    fn test_apply_rules_captures_bracketed_expression_whole() {
+        let rule = SsrRule::parse("foo($a) ==>> bar($a)", None).unwrap();
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn main() { foo(vec![1, 2, 3]); }".to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestions = apply_rules(&context, &[rule]);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].after_code, "bar(vec![1, 2, 3])");
+    }
+
+    /// 测试规则限定语言时，不匹配的语言不会产生建议
+    #[test]
+    fn test_apply_rules_respects_language_restriction() {
+        let rule = SsrRule::parse("foo($a) ==>> bar($a)", Some(Language::Python)).unwrap();
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn main() { foo(1); }".to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestions = apply_rules(&context, &[rule]);
+        assert!(suggestions.is_empty());
+    }
+
+    /// 测试没有匹配项时不会产生任何建议
+    #[test]
+    fn test_apply_rules_no_match_produces_no_suggestions() {
+        let rule = SsrRule::parse("foo($a) ==>> bar($a)", None).unwrap();
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: "fn main() { baz(1); }".to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestions = apply_rules(&context, &[rule]);
+        assert!(suggestions.is_empty());
+    }
+
+    /// 测试 Extract Method：一个只产生返回值、不依赖外部参数的语句范围
+    #[test]
+    fn test_extract_method_infers_return_value() {
+        let code = "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestion = extract_method(&context, 2, 3, Some("compute")).unwrap();
+
+        assert_eq!(suggestion.before_code, "    let a = 1;\n    let b = 2;\n");
+        assert_eq!(suggestion.after_code, "    let (a, b) = compute();");
+        let fix = suggestion.fix_suggestion.unwrap();
+        assert!(fix.diff.contains("fn compute<R1, R2>() -> (R1, R2) {"));
+        assert!(!fix.safe_to_auto_apply);
+    }
+
+    /// 测试 Extract Method：选中范围引用了外部已绑定的变量时，会被推断为参数
+    #[test]
+    fn test_extract_method_infers_parameter() {
+        let code = "fn main() {\n    let a = 1;\n    let b = a + 1;\n    println!(\"{}\", b);\n}\n";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let suggestion = extract_method(&context, 3, 3, Some("compute")).unwrap();
+
+        assert_eq!(suggestion.before_code, "    let b = a + 1;\n");
+        assert_eq!(suggestion.after_code, "    let b = compute(a);");
+    }
+
+    /// 测试越界的行范围会报错而不是 panic
+    #[test]
+    fn test_extract_method_rejects_out_of_bounds_range() {
+        let code = "fn main() {\n    let a = 1;\n}\n";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        assert!(extract_method(&context, 10, 20, None).is_err());
+    }
+
+    /// 测试选中范围只有空白内容时会报错
+    #[test]
+    fn test_extract_method_rejects_empty_range() {
+        let code = "fn main() {\n\n}\n";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let err = extract_method(&context, 2, 2, None).unwrap_err();
+        assert!(err.contains("empty"));
+    }
+
+    /// 测试选中范围包含 `return` 时会被拒绝，因为生成的调用无法表达其控制流
+    #[test]
+    fn test_extract_method_rejects_range_with_return() {
+        let code = "fn main() {\n    return;\n}\n";
+        let context = AnalysisContext {
+            file_path: PathBuf::from("test.rs"),
+            source_code: code.to_string(),
+            language: Language::Rust,
+        };
+
+        let err = extract_method(&context, 2, 2, None).unwrap_err();
+        assert!(err.contains("return"));
+    }
+}
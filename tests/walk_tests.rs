@@ -0,0 +1,72 @@
+//! Walk 模块单元测试
+
+#[cfg(test)]
+mod tests {
+    use codesage_analyzer::{AnalysisEngine, MetricsAnalyzer};
+    use codesage_walk::{Dispatcher, Reporter, Walker};
+    use std::fs;
+
+    /// 测试 Walker 能找到已知扩展名的文件并跳过被忽略的文件
+    #[test]
+    fn test_walker_finds_known_extensions_and_skips_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() {}").unwrap();
+        fs::write(dir.path().join("b.py"), "def b(): pass").unwrap();
+        fs::write(dir.path().join("readme.md"), "not a source file").unwrap();
+        fs::write(dir.path().join(".gitignore"), "ignored.rs\n").unwrap();
+        fs::write(dir.path().join("ignored.rs"), "fn ignored() {}").unwrap();
+
+        let files = Walker::walk(dir.path());
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| f.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert!(names.contains(&"a.rs".to_string()));
+        assert!(names.contains(&"b.py".to_string()));
+        assert!(!names.contains(&"readme.md".to_string()));
+        assert!(!names.contains(&"ignored.rs".to_string()));
+    }
+
+    /// 测试 Dispatcher 能跨多个文件并发分析并生成对应数量的报告
+    #[test]
+    fn test_dispatcher_analyzes_every_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() { let x = 1; }").unwrap();
+        fs::write(dir.path().join("b.rs"), "fn b() { let y = 2; }").unwrap();
+
+        let files = Walker::walk(dir.path());
+        let reports = Dispatcher::new(2).run(&files, || {
+            let mut engine = AnalysisEngine::new();
+            engine.register_analyzer(Box::new(MetricsAnalyzer::new()));
+            engine
+        });
+
+        assert_eq!(reports.len(), 2);
+    }
+
+    /// 测试 Reporter 按文件路径对汇总结果排序
+    #[test]
+    fn test_reporter_sorts_by_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("z.rs"), "fn z() {}").unwrap();
+        fs::write(dir.path().join("a.rs"), "fn a() {}").unwrap();
+
+        let files = Walker::walk(dir.path());
+        let reports = Dispatcher::new(1).run(&files, || {
+            let mut engine = AnalysisEngine::new();
+            engine.register_analyzer(Box::new(MetricsAnalyzer::new()));
+            engine
+        });
+        let report = Reporter::build(reports);
+
+        let paths: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| issue.location.file_path.clone())
+            .collect();
+        let mut sorted_paths = paths.clone();
+        sorted_paths.sort();
+        assert_eq!(paths, sorted_paths);
+    }
+}